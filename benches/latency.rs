@@ -1,7 +1,11 @@
-use criterion::{Criterion, criterion_group, criterion_main};
+use criterion::measurement::WallTime;
+use criterion::{BatchSize, BenchmarkGroup, Criterion, criterion_group, criterion_main};
+use rustc_hash::FxHasher;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault, RandomState};
 use std::hint::black_box;
 use zero_alloc_lob::engine::book::OrderBook;
-use zero_alloc_lob::storage::layout::Side;
+use zero_alloc_lob::storage::layout::{OrderId, Side};
 
 fn benchmark_place_order(c: &mut Criterion) {
     // Setup: Create a book with enough capacity so we don't OOM during the test
@@ -35,33 +39,70 @@ fn benchmark_place_order(c: &mut Criterion) {
     });
 }
 
-fn benchmark_match_order(c: &mut Criterion) {
-    let mut book = OrderBook::new("BTC-USDT", 1_000_000);
+/// Book of `ASK_LEVELS` resting sells, one per price from 50,000 up.
+///
+/// The match benchmarks take a fresh one per iteration: each taker consumes
+/// part of the top ask, so a shared book would run dry and the benchmark
+/// would silently start measuring non-crossing placements.
+const ASK_LEVELS: u64 = 1_000;
 
-    // Scenario: Book has 10,000 Sell orders.
-    // We want to measure the time it takes to MATCH against the top one.
-    for i in 0..10_000 {
+fn ask_ladder() -> OrderBook {
+    let mut book = OrderBook::new("BTC-USDT", ASK_LEVELS as usize + 1);
+    for i in 0..ASK_LEVELS {
         book.place_limit_order(i, Side::Sell, 50_000 + i, 100)
             .unwrap();
     }
+    book
+}
 
+fn benchmark_match_order(c: &mut Criterion) {
     let taker_id = 20_000;
 
     c.bench_function("execute_match_single", |b| {
-        b.iter(|| {
-            // Place a Buy order that crosses the spread (Matches the top Sell at 50,000)
-            // We use Fill-and-Kill style (qty 10 vs 100) so the resting order isn't removed,
-            // just reduced. This keeps the book structure stable.
-            let _ = black_box(book.place_limit_order(
-                black_box(taker_id),
-                black_box(Side::Buy),
-                black_box(55_000), // Crosses everything
-                black_box(10),     // Small qty
-            ));
+        b.iter_batched_ref(
+            ask_ladder,
+            |book| {
+                // A Buy that crosses the spread and takes 10 of the top
+                // ask's 100, so it trades once and leaves the maker resting.
+                let _ = black_box(book.place_limit_order(
+                    black_box(taker_id),
+                    black_box(Side::Buy),
+                    black_box(55_000), // Crosses everything
+                    black_box(10),     // Small qty
+                ));
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
 
-            // Note: In a real match benchmark, managing state is hard because orders disappear.
-            // This specific micro-benchmark measures the allocation + match logic overhead.
-        })
+/// `execute_match_single` with one reused trade buffer instead of a fresh
+/// `Vec` per call.
+///
+/// The capacity assertion inside the loop proves the buffer never
+/// reallocates, i.e. matching performs zero allocations per call.
+fn benchmark_match_order_into(c: &mut Criterion) {
+    let taker_id = 20_000;
+    let mut trades = Vec::with_capacity(16);
+    let capacity = trades.capacity();
+
+    c.bench_function("execute_match_single_into", |b| {
+        b.iter_batched_ref(
+            ask_ladder,
+            |book| {
+                trades.clear();
+                let _ = black_box(book.place_limit_order_into(
+                    black_box(taker_id),
+                    black_box(Side::Buy),
+                    black_box(55_000),
+                    black_box(10),
+                    &mut trades,
+                ));
+                assert_eq!(trades.len(), 1);
+                assert_eq!(trades.capacity(), capacity);
+            },
+            BatchSize::SmallInput,
+        )
     });
 }
 
@@ -83,7 +124,7 @@ fn benchmark_deep_insertion(c: &mut Criterion) {
             // We place a Sell order at 52,500.
             // This is worse than 50,000 but better than 54,999.
             // It should land roughly in the middle (index 2,500).
-            // The engine MUST walk 2,500 pointers to find the spot.
+            // The level index finds the spot without walking the 2,500 orders ahead.
             let _ = black_box(book.place_limit_order(
                 black_box(next_id),
                 black_box(Side::Sell),
@@ -96,10 +137,195 @@ fn benchmark_deep_insertion(c: &mut Criterion) {
     });
 }
 
+/// Builds a book with `depth` Sell orders, one per price level (50,000 + i).
+fn deep_book(depth: u64) -> OrderBook {
+    let mut book = OrderBook::new("BTC-USDT", 1_000_000);
+    for i in 0..depth {
+        book.place_limit_order(i, Side::Sell, 50_000 + i, 100)
+            .unwrap();
+    }
+    book
+}
+
+/// Middle-of-book insertion at increasing depths.
+///
+/// With the price-level index the cost should stay flat as depth grows
+/// (O(log L) lookup + O(1) link) instead of growing with the number of orders
+/// ahead of the new one.
+fn benchmark_insertion_depth_scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insertion_middle_by_depth");
+
+    for depth in [1_000u64, 10_000, 50_000] {
+        let mut book = deep_book(depth);
+        let middle = 50_000 + depth / 2;
+        let next_id = depth * 2;
+
+        // Case 1: The price level does not exist yet.
+        // Every price is occupied, so vacate the middle level first.
+        book.cancel_order(depth / 2).unwrap();
+        bench_insert_cancel(
+            &mut group,
+            format!("new_level/{depth}"),
+            &mut book,
+            next_id,
+            middle,
+        );
+
+        // Case 2: Append to an existing level in the middle of the book.
+        bench_insert_cancel(
+            &mut group,
+            format!("existing_level/{depth}"),
+            &mut book,
+            next_id,
+            middle + 1,
+        );
+    }
+
+    group.finish();
+}
+
+/// Places then cancels one Sell order at `price`, keeping the book stable.
+fn bench_insert_cancel(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    name: String,
+    book: &mut OrderBook,
+    id: u64,
+    price: u64,
+) {
+    group.bench_function(name, |b| {
+        b.iter(|| {
+            let _ = black_box(book.place_limit_order(
+                black_box(id),
+                black_box(Side::Sell),
+                black_box(price),
+                black_box(10),
+            ));
+            let _ = black_box(book.cancel_order(id));
+        })
+    });
+}
+
+/// `reprice_pegs` over 5,000 buy pegs following the best ask.
+///
+/// Pegs are indexed by the side they follow, so only a move of that side
+/// costs a sweep over them. Each iteration toggles one order and reprices:
+/// - `unchanged`: nothing moved, the call is O(1).
+/// - `other_side_moved`: the best bid flips, the buy pegs are not visited.
+/// - `reference_moved`: the best ask flips, all 5,000 pegs are re-queued.
+fn benchmark_reprice_pegs(c: &mut Criterion) {
+    const PEGS: u64 = 5_000;
+    let mut group = c.benchmark_group("reprice_pegs_5k");
+
+    let mut book = OrderBook::new("BTC-USDT", PEGS as usize + 16);
+    book.place_limit_order(1, Side::Buy, 40_000, 100).unwrap();
+    book.place_limit_order(2, Side::Sell, 50_000, 100).unwrap();
+    for i in 0..PEGS {
+        // Spread over 100 levels, all below the best bid.
+        book.place_pegged(1_000 + i, Side::Buy, -20_000 - (i % 100) as i64, 1)
+            .unwrap();
+    }
+    book.reprice_pegs();
+
+    group.bench_function("unchanged", |b| b.iter(|| black_box(book.reprice_pegs())));
+
+    let mut toggle = false;
+    group.bench_function("other_side_moved", |b| {
+        b.iter(|| {
+            toggle = !toggle;
+            let _ = match toggle {
+                true => book.place_limit_order(3, Side::Buy, 40_001, 1).map(|_| ()),
+                false => book.cancel_order(3).map(|_| ()),
+            };
+            black_box(book.reprice_pegs())
+        })
+    });
+    let _ = book.cancel_order(3);
+
+    let mut toggle = false;
+    group.bench_function("reference_moved", |b| {
+        b.iter(|| {
+            toggle = !toggle;
+            let _ = match toggle {
+                true => book.place_limit_order(4, Side::Sell, 49_999, 1).map(|_| ()),
+                false => book.cancel_order(4).map(|_| ()),
+            };
+            black_box(book.reprice_pegs())
+        })
+    });
+
+    group.finish();
+}
+
+/// First burst of orders into a brand-new book, with and without `prewarm`.
+///
+/// Each iteration gets a freshly constructed book (setup is not timed), so
+/// the cold case pays for first-touch page faults on the arena.
+fn benchmark_first_touch(c: &mut Criterion) {
+    const BURST: u64 = 100_000;
+    let mut group = c.benchmark_group("first_touch_burst_100k");
+
+    for prewarm in [false, true] {
+        let name = if prewarm { "prewarmed" } else { "cold" };
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || {
+                    let mut book = OrderBook::new("BTC-USDT", BURST as usize);
+                    if prewarm {
+                        book.prewarm();
+                    }
+                    book
+                },
+                |mut book| {
+                    // Each bid improves the best price, so insertion is O(1).
+                    for i in 0..BURST {
+                        let _ = black_box(book.place_limit_order(i, Side::Buy, 1_000 + i, 1));
+                    }
+                    book
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+/// `order_index` hashing: the default SipHash against Fx, with the
+/// insert + lookup + remove sequence a place/cancel pair performs.
+fn benchmark_order_index_hasher(c: &mut Criterion) {
+    const LIVE: u64 = 10_000;
+    let mut group = c.benchmark_group("order_index_insert_lookup_remove");
+
+    fn run<S: BuildHasher + Default>(group: &mut BenchmarkGroup<'_, WallTime>, name: &str) {
+        let mut index: HashMap<OrderId, u64, S> =
+            HashMap::with_capacity_and_hasher(LIVE as usize + 1, S::default());
+        for i in 0..LIVE {
+            index.insert(OrderId(i), i);
+        }
+        let id = OrderId(LIVE);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(index.contains_key(black_box(&id)));
+                index.insert(black_box(id), 0);
+                black_box(index.remove(black_box(&id)));
+            })
+        });
+    }
+
+    run::<RandomState>(&mut group, "siphash");
+    run::<BuildHasherDefault<FxHasher>>(&mut group, "fxhash");
+    group.finish();
+}
+
 criterion_group!(
     benches,
     benchmark_place_order,
     benchmark_match_order,
-    benchmark_deep_insertion
+    benchmark_match_order_into,
+    benchmark_deep_insertion,
+    benchmark_insertion_depth_scaling,
+    benchmark_reprice_pegs,
+    benchmark_first_touch,
+    benchmark_order_index_hasher
 );
 criterion_main!(benches);
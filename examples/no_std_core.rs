@@ -0,0 +1,37 @@
+//! Drives the book through `core` and `alloc` only, as a `no_std` caller
+//! would.
+//!
+//! `cargo run --example no_std_core --no-default-features` doubles as a
+//! compile check that the public API needs nothing from `std`. It exits
+//! with a panic if the book misbehaves, since there is no `println!`.
+//!
+//! A host binary still needs a global allocator and a panic handler, so
+//! `std` is linked for those alone; nothing below names it. On a bare-metal
+//! target the firmware provides both, and the library itself builds with
+//! `cargo build --no-default-features --target thumbv7em-none-eabihf`.
+#![no_std]
+
+extern crate alloc;
+extern crate std;
+
+use alloc::vec::Vec;
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::matcher::Trade;
+use zero_alloc_lob::storage::layout::{Price, Quantity, Side};
+
+fn main() {
+    let mut book = OrderBook::new("BTC-USD", 64);
+    book.place_limit_order(1, Side::Sell, 50_000, 2_000)
+        .unwrap();
+    book.place_limit_order(2, Side::Sell, 50_001, 1_000)
+        .unwrap();
+
+    let mut trades: Vec<Trade> = Vec::with_capacity(8);
+    book.place_limit_order_into(3, Side::Buy, 50_001, 2_500, &mut trades)
+        .unwrap();
+
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0].price, Price(50_000));
+    assert_eq!(book.best_ask_price(), Some(Price(50_001)));
+    assert_eq!(book.volume_at_price(Side::Sell, 50_001), Quantity(500));
+}
@@ -1,25 +1,169 @@
-use crate::engine::matcher::{self, Trade};
-use crate::storage::layout::{Order, OrderId, OrderPtr, Price, Quantity, Side};
-use llt_rs::arena_allocator::Arena;
-use std::collections::HashMap;
-use std::marker::PhantomData;
-use std::mem;
-use std::ptr::NonNull;
+use crate::engine::depth::{BookSnapshot, Depth, Ladder, Quote};
+use crate::engine::error::OrderBookError;
+use crate::engine::levels::LevelIndex;
+use crate::engine::matcher::{
+    self, FeeSchedule, MatchMode, MatchStream, PricingPolicy, SelfTradePrevention,
+    TakerConstraints, Trade, TradeAccumulation, TradeObserver,
+};
+use crate::engine::metrics::{ArenaStats, Metrics};
+use crate::engine::publish::TopOfBook;
+use crate::engine::rate_limit::{RateLimit, RateLimiter};
+use crate::engine::request::{
+    Command, ModifyMissingPolicy, OrderRequest, PlacementOutcome, QuantityLimits, TimeInForce,
+};
+use crate::engine::view::{self, SideView};
+use crate::storage::arena::Arena;
+use crate::storage::layout::{
+    Order, OrderId, OrderPtr, OrderView, Price, Quantity, RestMode, Side, compare_priority,
+};
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::format;
+#[cfg(feature = "std")]
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use core::fmt;
+use core::hash::BuildHasherDefault;
+use core::marker::PhantomData;
+use core::mem;
+use hashbrown::HashMap;
+use rustc_hash::FxHasher;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, Write};
+
+/// The `OrderId -> slot` index, hashed with Fx instead of the default SipHash.
+///
+/// Every placement, cancel and modify hashes an id at least once. Fx is one
+/// rotate, xor and multiply per `u64`, where SipHash runs several rounds for
+/// HashDoS resistance. That resistance matters for keys an attacker chooses
+/// freely; ids here come from the order-entry layer, which should assign or
+/// validate them before they reach the book.
+type OrderIndex = HashMap<OrderId, OrderPtr, BuildHasherDefault<FxHasher>>;
+
+/// What a limit placement returns: the resting slot (if any) and the trades.
+pub type PlaceResult = Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError>;
+
+/// Clock registered with `OrderBook::set_clock`. `Send` for the same reason
+/// as [`TradeObserver`].
+pub type Clock = Box<dyn FnMut() -> u64 + Send>;
 
 pub struct OrderBook {
     symbol: &'static str,
-    order_arena: Arena,
+    /// Order storage: the initial chunk plus one per [`reserve`](Self::reserve).
+    /// Chunks are only ever appended, and each owns a fixed heap block, so
+    /// no order ever moves.
+    order_arenas: Vec<Arena>,
 
     free_list: Vec<OrderPtr>,
 
-    order_index: HashMap<OrderId, OrderPtr>,
+    order_index: OrderIndex,
 
     pub(crate) best_bid: Option<OrderPtr>,
     pub(crate) best_ask: Option<OrderPtr>,
 
+    /// Monotonic counter bumped on every structural or quantity change.
+    pub(crate) version: u64,
+
+    /// Next intake/execution sequence number. See [`OrderBook::next_seq`].
+    pub(crate) seq_counter: u64,
+
+    /// Behaviour of `modify` when the target id is not resting.
+    modify_missing_policy: ModifyMissingPolicy,
+
+    /// Whether a modify may raise an order's quantity at all.
+    allow_modify_increase: bool,
+
+    /// Whether a same-price quantity increase keeps queue priority.
+    keep_priority_on_increase: bool,
+
+    /// Price grid for new orders (`0`/`1` = none).
+    tick_size: u64,
+
+    /// Lot size and size bounds for new orders (permissive by default).
+    quantity_limits: QuantityLimits,
+
+    /// Operator override: rejects every placement and modify while set.
+    kill_switch: bool,
+
+    /// Optional per-account order-entry throttle (off by default).
+    rate_limiter: Option<RateLimiter>,
+
+    /// Whether matching appends to `trade_accumulator` instead of returning trades.
+    trade_accumulation: TradeAccumulation,
+    trade_accumulator: Vec<Trade>,
+
+    /// Self-trade prevention policy (off by default).
+    pub(crate) self_trade_prevention: Option<SelfTradePrevention>,
+
+    /// How each crossed price level is shared among its makers.
+    pub(crate) match_mode: MatchMode,
+
+    /// Fee rates stamped on every trade (zero by default).
+    pub(crate) fee_schedule: FeeSchedule,
+
+    /// Maker price (default) or midpoint trade pricing.
+    pub(crate) pricing_policy: PricingPolicy,
+
+    /// Resting orders the matcher pulled without trading, oldest first.
+    pub(crate) auto_cancelled: Vec<OrderId>,
+
+    /// Parked stop-limit orders, oldest first.
+    stop_orders: Vec<StopOrder>,
+
+    /// Resting pegged orders, indexed by order side (`Side as usize`): buys
+    /// follow the best ask, sells the best bid. Grows on demand: not sized
+    /// to the arena.
+    pegs: [PegGroup; 2],
+
+    /// Price and quantity of the most recent trade (set by the matcher).
+    pub(crate) last_trade_price: Option<Price>,
+    pub(crate) last_trade_qty: Option<Quantity>,
+
+    /// Quantity traded over the book's lifetime (set by the matcher).
+    pub(crate) cumulative_volume: Quantity,
+
+    /// Called by the matcher for every trade as it is generated.
+    pub(crate) trade_observer: Option<TradeObserver>,
+
+    /// Stamps `Order::placed_at` when set (see [`set_clock`](Self::set_clock)).
+    clock: Option<Clock>,
+
+    /// Number of times `(best_bid, best_ask)` have been reassigned.
+    top_changes: (u64, u64),
+
+    /// Top of book as of the last `top_changed` call, tagged with its version.
+    last_quote: (u64, Quote),
+
+    /// Price-level index: insertion is a level lookup, not a walk over orders.
+    levels: LevelIndex,
+
+    /// Operation counters (see [`metrics`](Self::metrics)).
+    pub(crate) metrics: Metrics,
+
+    /// Most orders ever resting at once, and slots ever handed to an order.
+    /// Lifetime figures behind [`arena_stats`](Self::arena_stats): `clear`
+    /// does not reset them and `prewarm` placeholders do not count.
+    peak_slots: usize,
+    slot_allocations: u64,
+
     _marker: PhantomData<Order>,
 }
 
+// SAFETY: Every `OrderPtr` the book holds (`best_bid`, `best_ask`, the index,
+// the free list, the level index and the `next`/`prev` links) points into
+// `order_arenas`, which the book owns outright. Moving the book moves the
+// arenas' heap blocks with it without relocating them, so the pointers stay
+// valid, and no other value can hold a pointer into them (`OrderPtr`s handed
+// to callers borrow nothing and are only dereferenced through the book). The
+// boxed callbacks are `Send` by type. The book is deliberately **not** `Sync`:
+// `&self` methods read through the same pointers `&mut self` methods write
+// through, so shared access from two threads is never allowed.
+unsafe impl Send for OrderBook {}
+
 impl OrderBook {
     pub fn new(symbol: &'static str, capacity: usize) -> Self {
         let order_size = mem::size_of::<Order>();
@@ -27,12 +171,43 @@ impl OrderBook {
 
         Self {
             symbol,
-            order_arena: Arena::new(total_bytes),
+            order_arenas: vec![Arena::new(total_bytes)],
             // Reserve space for the pointers so 'push' never allocates
             free_list: Vec::with_capacity(capacity),
-            order_index: HashMap::with_capacity(capacity),
+            order_index: OrderIndex::with_capacity_and_hasher(capacity, Default::default()),
             best_bid: None,
             best_ask: None,
+            version: 0,
+            trade_accumulation: TradeAccumulation::Off,
+            trade_accumulator: Vec::new(),
+            self_trade_prevention: None,
+            match_mode: MatchMode::PriceTime,
+            fee_schedule: FeeSchedule::default(),
+            pricing_policy: PricingPolicy::default(),
+            // Every maker pulled in one placement was a live order, so a
+            // drained list never outgrows `capacity` ids
+            auto_cancelled: Vec::with_capacity(capacity),
+            stop_orders: Vec::new(),
+            pegs: Default::default(),
+            last_trade_price: None,
+            last_trade_qty: None,
+            cumulative_volume: Quantity(0),
+            trade_observer: None,
+            clock: None,
+            top_changes: (0, 0),
+            last_quote: (0, Quote::default()),
+            seq_counter: 0,
+            modify_missing_policy: ModifyMissingPolicy::Reject,
+            allow_modify_increase: false,
+            keep_priority_on_increase: false,
+            tick_size: 0,
+            quantity_limits: QuantityLimits::default(),
+            kill_switch: false,
+            rate_limiter: None,
+            levels: LevelIndex::default(),
+            metrics: Metrics::default(),
+            peak_slots: 0,
+            slot_allocations: 0,
             _marker: PhantomData,
         }
     }
@@ -47,197 +222,2821 @@ impl OrderBook {
         side: Side,
         price: u64,
         qty: u64,
-    ) -> Result<(Option<OrderPtr>, Vec<Trade>), String> {
-        let price = Price(price);
-        let qty = Quantity(qty);
-        let order_id = OrderId(id);
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.place_limit_order_with(id, side, price, qty, TakerConstraints::default())
+    }
 
-        if self.order_index.contains_key(&order_id) {
-            return Err(format!("Duplicate Order ID: {}", order_id.0));
+    /// Places a limit order whose taking phase honours `constraints`
+    /// (e.g. a minimum fill per maker). See [`TakerConstraints`].
+    pub fn place_limit_order_with(
+        &mut self,
+        id: u64,
+        side: Side,
+        price: u64,
+        qty: u64,
+        constraints: TakerConstraints,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.place(OrderRequest {
+            constraints,
+            ..OrderRequest::limit(id, side, price, qty)
+        })
+    }
+
+    /// Places a limit order with the given [`TimeInForce`].
+    ///
+    /// `Ioc` and `Fok` never rest: the pointer is always `None` and the id is
+    /// never indexed, so a later `cancel_order` with it returns `OrderNotFound`.
+    pub fn place_limit_order_tif(
+        &mut self,
+        id: u64,
+        side: Side,
+        price: u64,
+        qty: u64,
+        tif: TimeInForce,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.place(OrderRequest {
+            tif,
+            ..OrderRequest::limit(id, side, price, qty)
+        })
+    }
+
+    /// Places a maker-only limit order.
+    ///
+    /// If the price would cross the opposite best (touching counts: a buy at
+    /// the best ask crosses), it is rejected with
+    /// `OrderBookError::PostOnlyWouldCross` before any matching or allocation.
+    /// Otherwise it rests exactly like [`place_limit_order`](Self::place_limit_order).
+    pub fn place_post_only(
+        &mut self,
+        id: u64,
+        side: Side,
+        price: u64,
+        qty: u64,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.place(OrderRequest {
+            post_only: true,
+            ..OrderRequest::limit(id, side, price, qty)
+        })
+    }
+
+    /// Places a pegged order: a maker-only limit order priced at the opposite
+    /// best plus `offset` (in `Price` units, on the tick grid), repriced by
+    /// [`reprice_pegs`](Self::reprice_pegs).
+    ///
+    /// A buy pegs to the best ask, a sell to the best bid, so `offset` is
+    /// normally negative for a buy and positive for a sell. A price that
+    /// would cross (or lock) the book is clamped to one tick short of the
+    /// opposite best, so a peg never takes liquidity. Rejected with
+    /// `InvalidPrice` if the opposite side is empty (nothing to peg to) or
+    /// `offset` is off the tick grid.
+    ///
+    /// Pegs live in the book as ordinary resting orders with
+    /// `Order::pegged` set: they match, cancel and modify like any other. A
+    /// crossing modify re-places one as a plain limit order. Snapshots and
+    /// CSV dumps keep the order but not the peg.
+    ///
+    /// **Allocation:** the order itself takes an arena slot like any other,
+    /// but the peg's offset goes into a side table that is not reserved up
+    /// front. It grows with the number of pegs, so placing a peg may
+    /// allocate; keep pegs off a strictly allocation-free path. Placement
+    /// also scans the table once for a stale entry under the same id, so it
+    /// is O(P) in the pegs resting.
+    pub fn place_pegged(
+        &mut self,
+        id: u64,
+        side: Side,
+        offset: i64,
+        qty: u64,
+    ) -> Result<OrderPtr, OrderBookError> {
+        if !self.on_tick(offset.unsigned_abs()) {
+            return Err(OrderBookError::InvalidPrice);
+        }
+        let price = self
+            .peg_price(side, offset)
+            .ok_or(OrderBookError::InvalidPrice)?;
+        let (order_ptr, _) = self.place_post_only(id, side, price.0, qty)?;
+        let mut order_ptr = order_ptr.ok_or(OrderBookError::PostOnlyWouldCross(OrderId(id)))?;
+        unsafe {
+            order_ptr.as_mut().pegged = true;
         }
 
-        // --- STEP 1: MATCHING (TAKER) ---
-        let (remaining_qty, trades) = matcher::execute_match(self, order_id, side, price, qty);
+        let id = OrderId(id);
+        for group in &mut self.pegs {
+            group.pegs.retain(|peg| peg.id != id);
+        }
+        // Placing on `side` leaves the opposite best, the peg's reference, as is.
+        let reference = match side {
+            Side::Buy => self.best_ask_price(),
+            Side::Sell => self.best_bid_price(),
+        };
+        let group = &mut self.pegs[side as usize];
+        if group.reference != reference {
+            // Priced against a reference the rest of the group may not be:
+            // the next `reprice_pegs` must visit them all.
+            group.reference = None;
+        }
+        group.pegs.push(Peg { id, offset });
+        Ok(order_ptr)
+    }
 
-        if remaining_qty.0 == 0 {
-            return Ok((None, trades));
+    /// Moves every pegged order to its current peg price (opposite best plus
+    /// offset, clamped as in [`place_pegged`](Self::place_pegged)) and
+    /// returns how many moved.
+    ///
+    /// Call it after anything that may have changed the top of book (see
+    /// [`top_changed`](Self::top_changed)); the book never reprices on its
+    /// own. A peg already at its price keeps its queue position. One that
+    /// moves is **re-queued** at the back of its new level, losing time
+    /// priority, exactly like a price amend. A peg whose opposite side is
+    /// empty stays where it is.
+    ///
+    /// **Cost:** pegs are grouped by the side they follow, and each group
+    /// remembers the reference price it was last priced against. A group
+    /// whose reference has not moved is skipped in O(1), so a call after a
+    /// move on one side only touches the pegs following that side (O(P) for
+    /// P of them), and a call after no move costs nothing. A consequence: a
+    /// pegged order moved by a modify keeps its new price until its
+    /// reference next moves.
+    ///
+    /// Buy pegs are repriced before sell pegs, each group oldest first. A
+    /// peg that references another peg (a buy pegged to an ask that is
+    /// itself a pegged sell) may need a second call to settle.
+    pub fn reprice_pegs(&mut self) -> usize {
+        self.reprice_peg_group(Side::Buy) + self.reprice_peg_group(Side::Sell)
+    }
+
+    /// [`reprice_pegs`](Self::reprice_pegs) for the pegged orders on `side`.
+    fn reprice_peg_group(&mut self, side: Side) -> usize {
+        // Repricing orders on `side` never moves the opposite best, so the
+        // reference holds for the whole group.
+        let reference = match side {
+            Side::Buy => self.best_ask_price(),
+            Side::Sell => self.best_bid_price(),
+        };
+        let group = &mut self.pegs[side as usize];
+        if reference.is_none() || group.reference == reference {
+            return 0;
         }
+        group.reference = reference;
 
-        // --- STEP 2: PLACEMENT (MAKER) ---
-        let new_order_data = Order::new(order_id, side, price, remaining_qty);
+        // Drop pegs that filled or were cancelled, including ids since
+        // reused by a plain order.
+        let mut pegs = mem::take(&mut group.pegs);
+        pegs.retain(|peg| {
+            self.order_index
+                .get(&peg.id)
+                .is_some_and(|ptr| unsafe { ptr.as_ref() }.pegged)
+        });
 
-        // ALLOCATION STRATEGY:
-        // 1. Check the Free List (O(1) Pop)
-        // 2. If empty, Bump Allocate from Arena (O(1) Pointer bump)
-        let order_ptr = if let Some(mut recycled_ptr) = self.free_list.pop() {
-            // RECYCLING: We are writing new data into an "old" memory address
+        let mut moved = 0;
+        for &Peg { id, offset } in &pegs {
+            let order_ptr = self.order_index[&id];
+            let order = unsafe { order_ptr.as_ref() };
+            let Some(price) = self.peg_price(side, offset) else {
+                continue;
+            };
+            if price == order.price {
+                continue;
+            }
+            // SAFETY: `peg_price` never crosses the opposite best.
+            let qty = order.qty;
             unsafe {
-                *recycled_ptr.as_mut() = new_order_data;
+                self.relink(order_ptr, price, qty);
+            }
+            self.metrics.orders_modified += 1;
+            moved += 1;
+        }
+        self.pegs[side as usize].pegs = pegs;
+        moved
+    }
+
+    /// Opposite best plus `offset`, clamped to the non-crossing side of the
+    /// book and to valid prices. `None` if the opposite side is empty or
+    /// leaves no valid non-crossing price.
+    fn peg_price(&self, side: Side, offset: i64) -> Option<Price> {
+        let tick = self.tick_size.max(1);
+        let price = match side {
+            Side::Buy => {
+                let ask = self.best_ask_price()?.0;
+                let passive = ask.checked_sub(tick).filter(|&p| p > 0)?;
+                ask.saturating_add_signed(offset).clamp(tick, passive)
+            }
+            Side::Sell => {
+                let bid = self.best_bid_price()?.0;
+                let highest = u64::MAX - u64::MAX % tick;
+                let passive = bid.checked_add(tick).filter(|&p| p <= highest)?;
+                bid.saturating_add_signed(offset).clamp(passive, highest)
             }
-            recycled_ptr
-        } else {
-            // ALLOCATION: New memory from the big block
-            let order_ref = self.order_arena.alloc(new_order_data);
-            unsafe { NonNull::new_unchecked(order_ref as *mut Order) }
         };
+        Some(Price(price))
+    }
 
-        // INSERTION (O(N) - Price-Time Priority)
-        unsafe {
-            self.insert_sorted(order_ptr, side, price);
+    /// Places an iceberg order: `total` in all, of which at most `display`
+    /// is visible at a time.
+    ///
+    /// On arrival the full `total` takes liquidity like any limit order. The
+    /// remainder rests showing a `display` slice; depth, totals and `qty`
+    /// only ever see that slice. Each time a slice is fully consumed the
+    /// matcher refills it from the hidden reserve and moves the order to the
+    /// back of its price level, so a refill loses time priority to every
+    /// order already queued at that price. `display` must be non-zero and no
+    /// larger than `total` (`InvalidQuantity` otherwise).
+    pub fn place_iceberg(
+        &mut self,
+        id: u64,
+        side: Side,
+        price: u64,
+        display: u64,
+        total: u64,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        if display == 0 {
+            return Err(OrderBookError::InvalidQuantity);
         }
+        self.place(OrderRequest {
+            display_qty: Quantity(display),
+            ..OrderRequest::limit(id, side, price, total)
+        })
+    }
 
-        // INDEXING (CONTROL PLANE)
-        self.order_index.insert(order_id, order_ptr);
+    /// Places an order described by a full [`OrderRequest`].
+    ///
+    /// This is the entry point every `place_*` convenience method funnels into.
+    /// `req.tif` and `req.post_only` select the behaviour (see
+    /// [`OrderRequest`] for the valid combinations); contradictory flags are
+    /// rejected with `OrderBookError::InvalidOrderType` before anything changes,
+    /// as are a zero price (`InvalidPrice`) and a zero quantity
+    /// (`InvalidQuantity`). If a remainder would need to rest but the arena
+    /// and free list are exhausted, the order is rejected with
+    /// `OrderBookError::ArenaFull` before any matching, so no fills are lost.
+    /// An IOC remainder and a killed FOK return `(None, trades)`, the latter
+    /// with no trades.
+    pub fn place(
+        &mut self,
+        req: OrderRequest,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        let (_, order_ptr, trades) = self.place_inner(req)?;
+        Ok((order_ptr, trades))
+    }
 
-        Ok((Some(order_ptr), trades))
+    /// Matches a taker lazily: the returned [`MatchStream`] fills one maker per
+    /// `next()`, so a large sweep can be settled trade by trade without
+    /// buffering.
+    ///
+    /// Always matches in price-time order, whatever the [`MatchMode`]:
+    /// pro-rata allocation needs a whole level at once. Like
+    /// [`execute_match`](matcher::execute_match), this is a raw match and
+    /// skips order-entry checks (kill switch, rate limit, quantity limits).
+    /// The taker never rests: whatever is unfilled when the stream is dropped
+    /// is discarded. The stream does not trigger stop orders itself; they are
+    /// checked against the last trade price on the next placement.
+    pub fn match_stream(&mut self, id: u64, side: Side, price: u64, qty: u64) -> MatchStream<'_> {
+        MatchStream::new(self, OrderRequest::limit(id, side, price, qty))
     }
 
-    /// Modifies an existing order.
-    pub fn modify_order(
+    /// Places a ladder of plain limit orders `(id, side, price, qty)` in
+    /// order, returning one result per entry at the same index.
+    ///
+    /// Each entry is independent: a rejected one (duplicate id, `ArenaFull`,
+    /// ...) leaves the book untouched and the batch carries on, while every
+    /// placement before it stands. The results `Vec` is allocated once, at
+    /// its final size.
+    pub fn place_batch(&mut self, orders: &[(u64, Side, u64, u64)]) -> Vec<PlaceResult> {
+        let mut results = Vec::with_capacity(orders.len());
+        for &(id, side, price, qty) in orders {
+            results.push(self.place_limit_order(id, side, price, qty));
+        }
+        results
+    }
+
+    /// Executes a market order: takes liquidity at any price, never rests.
+    ///
+    /// Returns the **unfilled** quantity alongside the trades. It is non-zero
+    /// when the opposite side ran dry; an empty opposite side yields
+    /// `(Quantity(qty), vec![])`, not an error. Since nothing ever rests, the
+    /// free list and arena are never touched.
+    pub fn place_market_order(
         &mut self,
         id: u64,
-        new_price: u64,
-        new_qty: u64,
-    ) -> Result<(Option<OrderPtr>, Vec<Trade>), String> {
-        let order_id = OrderId(id);
-        let new_price = Price(new_price);
-        let new_qty = Quantity(new_qty);
+        side: Side,
+        qty: u64,
+    ) -> Result<(Quantity, Vec<Trade>), OrderBookError> {
+        self.place_market_order_with(id, side, qty, TakerConstraints::default())
+    }
 
-        let mut order_ptr = match self.order_index.get(&order_id) {
-            Some(ptr) => *ptr,
-            None => return Err(format!("Order ID {} not found.", id)),
+    /// Executes a market order whose sweep honours `constraints`. With
+    /// `max_slippage` set, it stops at the first maker more than that far
+    /// from the opposite best and returns the rest as unfilled. See
+    /// [`TakerConstraints`].
+    pub fn place_market_order_with(
+        &mut self,
+        id: u64,
+        side: Side,
+        qty: u64,
+        constraints: TakerConstraints,
+    ) -> Result<(Quantity, Vec<Trade>), OrderBookError> {
+        // The most aggressive limit crosses every maker, so the cross check
+        // always passes and the walk stops only when `qty` or the side runs out.
+        // Both extremes sit on the tick grid, so the tick check passes too.
+        let tick = self.tick_size.max(1);
+        let market_price = match side {
+            Side::Buy => u64::MAX - u64::MAX % tick,
+            Side::Sell => tick, // Lowest valid price
         };
+        let (unfilled_qty, _, trades) = self.place_inner(OrderRequest {
+            constraints,
+            tif: TimeInForce::Ioc,
+            market: true,
+            ..OrderRequest::limit(id, side, market_price, qty)
+        })?;
+        Ok((unfilled_qty, trades))
+    }
 
-        // Safety: We hold mutable reference to book
-        let order = unsafe { order_ptr.as_mut() };
+    /// Shared placement path. Also returns the quantity that did not trade
+    /// (resting or cancelled).
+    ///
+    /// Any stop orders triggered by the resulting trades are submitted
+    /// afterwards and their trades appended (see
+    /// [`place_stop_limit`](Self::place_stop_limit)).
+    fn place_inner(
+        &mut self,
+        req: OrderRequest,
+    ) -> Result<(Quantity, Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.check_kill_switch()?;
+        self.check_rate_limit(&req)?;
 
-        // FAST PATH: Price match + Qty reduction
-        if order.price == new_price && new_qty <= order.qty {
-            order.qty = new_qty;
-            if new_qty.0 == 0 {
-                self.cancel_order(id)?;
-                return Ok((None, vec![]));
+        let (unfilled_qty, order_ptr, mut trades) = self.submit(req, None)?;
+        self.activate_stops(&mut trades, false);
+        Ok((unfilled_qty, order_ptr, trades))
+    }
+
+    /// Places a plain limit order, appending its trades to `trades`.
+    ///
+    /// The allocation-free twin of [`place_limit_order`](Self::place_limit_order):
+    /// no `Vec` is created per call, so a hot loop can `clear()` and reuse one
+    /// buffer forever. The buffer only allocates if a sweep outgrows its
+    /// capacity. Trades of any stop orders this placement triggers are
+    /// appended too. Trade accumulation is bypassed: everything goes to
+    /// `trades`.
+    pub fn place_limit_order_into(
+        &mut self,
+        id: u64,
+        side: Side,
+        price: u64,
+        qty: u64,
+        trades: &mut Vec<Trade>,
+    ) -> Result<Option<OrderPtr>, OrderBookError> {
+        let req = OrderRequest::limit(id, side, price, qty);
+        self.check_kill_switch()?;
+        self.check_rate_limit(&req)?;
+
+        let (_, order_ptr, _) = self.submit(req, Some(&mut *trades))?;
+        self.activate_stops(trades, true);
+        Ok(order_ptr)
+    }
+
+    /// Validates, matches and (if it may rest) books `req`. Entry checks
+    /// (kill switch, rate limit) are the caller's job.
+    ///
+    /// Trades are appended to `caller_buf` if given, and the returned `Vec`
+    /// is then empty. Otherwise they are returned, or appended to the
+    /// accumulator while trade accumulation is on.
+    fn submit(
+        &mut self,
+        req: OrderRequest,
+        caller_buf: Option<&mut Vec<Trade>>,
+    ) -> Result<(Quantity, Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        let OrderRequest {
+            id: order_id,
+            side,
+            price,
+            qty,
+            ..
+        } = req;
+        let constraints = req.taker_constraints();
+
+        if self.order_index.contains_key(&order_id) {
+            return Err(OrderBookError::DuplicateId(order_id));
+        }
+
+        self.check_order_type(&req)?;
+        if req.tif == TimeInForce::Fok
+            && !matcher::can_fill_with(self, side, price, qty, constraints)
+        {
+            return Ok((qty, None, Vec::new()));
+        }
+
+        if let TradeAccumulation::Capped(cap) = self.trade_accumulation
+            && caller_buf.is_none()
+        {
+            let (_, trade_count) = matcher::match_footprint(self, side, price, qty, constraints);
+            if self.trade_accumulator.len() + trade_count > cap {
+                return Err(OrderBookError::TradeBufferFull);
             }
-            return Ok((Some(order_ptr), vec![]));
         }
 
-        // SLOW PATH: Price change or Qty increase -> Loss of Priority
-        let side = order.side;
-        self.cancel_order(id)?;
-        self.place_limit_order(id, side, new_price.0, new_qty.0)
-    }
+        // ARENA EXHAUSTION: Fills cannot be rolled back, so a remainder that
+        // would have nowhere to rest is rejected *before* matching. The dry run
+        // only happens once the arena is actually full (O(1) check otherwise).
+        // Conservative, like `try_place`: slots this order would free by
+        // filling makers are not counted.
+        if req.tif == TimeInForce::Gtc && !self.has_free_slot() {
+            let (remaining_qty, _) = matcher::match_footprint(self, side, price, qty, constraints);
+            if remaining_qty.0 > 0 {
+                return Err(OrderBookError::ArenaFull);
+            }
+        }
 
-    pub fn cancel_order(&mut self, id: u64) -> Result<OrderId, String> {
-        let order_id = OrderId(id);
+        // Stamp intake order before any trade this order generates.
+        let seq = self.take_seq();
+        self.metrics.orders_placed += 1;
 
-        let order_ptr = match self.order_index.remove(&order_id) {
-            Some(ptr) => ptr,
-            None => return Err(format!("Order ID {} not found in book.", id)),
+        // --- STEP 1: MATCHING (TAKER) ---
+        let (remaining_qty, trades) = if let Some(buf) = caller_buf {
+            (matcher::match_request(self, &req, buf), Vec::new())
+        } else if self.trade_accumulation == TradeAccumulation::Off {
+            // Starts unallocated: the matcher reserves a sweep's worth on the
+            // first trade, so purely resting orders never touch the heap.
+            let mut trades = Vec::new();
+            let remaining_qty = matcher::match_request(self, &req, &mut trades);
+            (remaining_qty, trades)
+        } else {
+            // Take the buffer out so the matcher can borrow the book mutably.
+            let mut accumulator = mem::take(&mut self.trade_accumulator);
+            let remaining_qty = matcher::match_request(self, &req, &mut accumulator);
+            self.trade_accumulator = accumulator;
+            (remaining_qty, Vec::new())
         };
 
-        // 1. O(1) Unlink
-        self.remove_order(order_ptr);
+        if remaining_qty.0 == 0 {
+            return Ok((remaining_qty, None, trades));
+        }
 
-        // 2. O(1) Recycle: Push the pointer onto the free list stack
-        self.free_list.push(order_ptr);
+        if req.tif != TimeInForce::Gtc || self.would_cross(side, price) {
+            return Ok((remaining_qty, None, trades));
+        }
 
-        Ok(order_id)
-    }
+        // --- STEP 2: PLACEMENT (MAKER) ---
+        let was_crossed = self.is_crossed();
+        let placed_at = self.placement_time(&req);
+        let new_order_data = resting_order(&req, seq, placed_at, remaining_qty);
 
-    pub(crate) fn remove_order(&mut self, mut ptr: OrderPtr) {
+        // ALLOCATION STRATEGY:
+        // 1. Check the Free List (O(1) Pop)
+        // 2. If empty, Bump Allocate from Arena (O(1) Pointer bump)
+        // The pre-check above guarantees a slot, so this never fails in practice.
+        let order_ptr = self
+            .try_alloc_slot(new_order_data)
+            .ok_or(OrderBookError::ArenaFull)?;
+
+        // INSERTION (O(N) - Price-Time Priority)
         unsafe {
-            let order = ptr.as_mut();
-            let next_ptr = order.next;
-            let prev_ptr = order.prev;
+            self.insert_sorted(order_ptr);
+        }
 
-            if let Some(mut next) = next_ptr {
-                next.as_mut().prev = prev_ptr;
-            }
+        // INDEXING (CONTROL PLANE)
+        self.order_index.insert(order_id, order_ptr);
 
-            if let Some(mut prev) = prev_ptr {
-                prev.as_mut().next = next_ptr;
-            }
+        // Resting a remainder must never cross the book. A book restored
+        // already crossed is exempt until it is uncrossed.
+        debug_assert!(
+            was_crossed || !self.is_crossed(),
+            "matcher left the book crossed after placing {order_id:?}"
+        );
 
-            if self.best_bid == Some(ptr) {
-                self.best_bid = next_ptr;
-            }
-            if self.best_ask == Some(ptr) {
-                self.best_ask = next_ptr;
-            }
+        Ok((remaining_qty, Some(order_ptr), trades))
+    }
 
-            order.next = None;
-            order.prev = None;
+    /// Hard real-time placement: never panics and never allocates.
+    ///
+    /// Guarantees, checked **before** any state changes:
+    /// - Trades are appended to `trade_buf` only if they all fit in its spare
+    ///   capacity, otherwise [`OrderBookError::TradeBufferFull`]. The buffer never grows.
+    /// - If a remainder would rest, a slot must be available (free list or
+    ///   arena), otherwise [`OrderBookError::ArenaFull`].
+    /// - Live orders never exceed `capacity`, the size `order_index` was
+    ///   reserved for, so inserting into it can never trigger a growing rehash.
+    /// - Makers pulled without trading (`CancelIfCrossed`, self-trade
+    ///   prevention) are reported into a list reserved for `capacity` ids.
+    ///   It only grows if [`drain_auto_cancelled`](Self::drain_auto_cancelled)
+    ///   is skipped until more than that many have piled up.
+    ///
+    /// Stop orders are **not** triggered here, since their trades could
+    /// outgrow `trade_buf`: a stop the trades of this call have reached fires
+    /// on the next [`place`](Self::place) (or other allocating placement).
+    ///
+    /// A rejected request leaves the book untouched.
+    pub fn try_place(
+        &mut self,
+        req: OrderRequest,
+        trade_buf: &mut Vec<Trade>,
+    ) -> Result<PlacementOutcome, OrderBookError> {
+        self.check_kill_switch()?;
+        self.check_rate_limit(&req)?;
+
+        if self.order_index.contains_key(&req.id) {
+            return Err(OrderBookError::DuplicateId(req.id));
         }
-    }
 
-    unsafe fn insert_sorted(&mut self, mut new_ptr: OrderPtr, side: Side, price: Price) {
-        let mut current_ptr = match side {
-            Side::Buy => self.best_bid,
-            Side::Sell => self.best_ask,
-        };
+        self.check_order_type(&req)?;
 
-        let mut prev_ptr: Option<OrderPtr> = None;
+        // --- STEP 1: PRE-FLIGHT (READ-ONLY) ---
+        let (remaining_qty, trade_count) =
+            matcher::match_footprint(self, req.side, req.price, req.qty, req.taker_constraints());
 
-        while let Some(curr) = current_ptr {
-            let curr_order = unsafe { curr.as_ref() };
+        if req.tif == TimeInForce::Fok && remaining_qty.0 > 0 {
+            return Ok(PlacementOutcome::Cancelled);
+        }
 
-            let should_insert_before = match side {
-                Side::Buy => price > curr_order.price,
-                Side::Sell => price < curr_order.price,
-            };
+        if trade_count > trade_buf.capacity() - trade_buf.len() {
+            return Err(OrderBookError::TradeBufferFull);
+        }
+        // Conservative: a remainder left by skipped makers is cancelled rather
+        // than rested, but we still insist on a slot for it.
+        if remaining_qty.0 > 0 && req.tif == TimeInForce::Gtc && !self.has_free_slot() {
+            return Err(OrderBookError::ArenaFull);
+        }
 
-            if should_insert_before {
-                break;
-            }
-            prev_ptr = Some(curr);
-            current_ptr = curr_order.next;
+        let seq = self.take_seq();
+        self.metrics.orders_placed += 1;
+
+        // --- STEP 2: MATCHING (TAKER) ---
+        let remaining_qty = matcher::match_request(self, &req, trade_buf);
+
+        if remaining_qty.0 == 0 {
+            return Ok(PlacementOutcome::Filled);
         }
+        if req.tif != TimeInForce::Gtc || self.would_cross(req.side, req.price) {
+            return Ok(PlacementOutcome::Cancelled);
+        }
+
+        // --- STEP 3: PLACEMENT (MAKER) ---
+        let was_crossed = self.is_crossed();
+        let placed_at = self.placement_time(&req);
+        let order_data = resting_order(&req, seq, placed_at, remaining_qty);
+        let order_ptr = self
+            .try_alloc_slot(order_data)
+            .ok_or(OrderBookError::ArenaFull)?;
 
         unsafe {
-            new_ptr.as_mut().next = current_ptr;
+            self.insert_sorted(order_ptr);
         }
-        unsafe {
-            new_ptr.as_mut().prev = prev_ptr;
+        self.order_index.insert(req.id, order_ptr);
+        debug_assert!(
+            was_crossed || !self.is_crossed(),
+            "matcher left the book crossed after placing {:?}",
+            req.id
+        );
+
+        Ok(PlacementOutcome::Resting(order_ptr))
+    }
+
+    /// Parks a stop-limit order until the market trades through `trigger`.
+    ///
+    /// A buy stop triggers once the last trade price is at or above `trigger`,
+    /// a sell stop once it is at or below. The triggered order is then
+    /// submitted as a plain GTC limit order at `limit`. If the last trade has
+    /// already reached `trigger`, it is submitted immediately and its trades
+    /// returned; otherwise the returned `Vec` is empty.
+    ///
+    /// **Cascades:** a triggered order's own fills can trigger further stops.
+    /// Activation runs to a fixed point after every placement, submitting
+    /// triggered stops oldest first and re-checking the last trade price after
+    /// each one, so one sweep can fire a whole chain. Their trades are appended
+    /// to the trades of the placement that set them off. A triggered stop that
+    /// is rejected (e.g. `ArenaFull`) is dropped and reported through
+    /// [`drain_auto_cancelled`](Self::drain_auto_cancelled).
+    ///
+    /// Parked stops are not resting orders: they are invisible to depth and
+    /// iteration and are not part of [`serialize`](Self::serialize). A parked
+    /// stop can be pulled with [`cancel_order`](Self::cancel_order).
+    /// Trades made through `try_place` do not activate stops; they fire on the
+    /// next `place`.
+    pub fn place_stop_limit(
+        &mut self,
+        id: u64,
+        side: Side,
+        trigger: u64,
+        limit: u64,
+        qty: u64,
+    ) -> Result<Vec<Trade>, OrderBookError> {
+        self.check_kill_switch()?;
+
+        let id = OrderId(id);
+        if self.order_index.contains_key(&id) || self.stop_orders.iter().any(|s| s.id == id) {
+            return Err(OrderBookError::DuplicateId(id));
+        }
+        if trigger == 0 || limit == 0 || !self.on_tick(trigger) || !self.on_tick(limit) {
+            return Err(OrderBookError::InvalidPrice);
+        }
+        if !self.quantity_limits.allows(qty) {
+            return Err(OrderBookError::InvalidQuantity);
         }
 
-        if let Some(mut curr) = current_ptr {
-            unsafe {
-                curr.as_mut().prev = Some(new_ptr);
-            }
+        self.stop_orders.push(StopOrder {
+            id,
+            side,
+            trigger: Price(trigger),
+            limit: Price(limit),
+            qty: Quantity(qty),
+        });
+
+        let mut trades = Vec::new();
+        self.activate_stops(&mut trades, false);
+        Ok(trades)
+    }
+
+    /// Price of the most recent trade, if the book has traded.
+    pub fn last_trade_price(&self) -> Option<Price> {
+        self.last_trade_price
+    }
+
+    /// Quantity of the most recent trade, if the book has traded.
+    pub fn last_trade_qty(&self) -> Option<Quantity> {
+        self.last_trade_qty
+    }
+
+    /// Running operation counters. See [`Metrics`].
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Slot usage of the order arena. See [`ArenaStats`].
+    pub fn arena_stats(&self) -> ArenaStats {
+        // Every allocation past the high-water mark landed in a slot an
+        // earlier order had already used.
+        let reuses = self.slot_allocations - self.peak_slots as u64;
+        ArenaStats {
+            allocated_slots: self.peak_slots,
+            live_slots: self.active_orders(),
+            free_slots: self.free_slots(),
+            reuse_ratio: match self.slot_allocations {
+                0 => 0.0,
+                allocations => reuses as f64 / allocations as f64,
+            },
         }
-        if let Some(mut prev) = prev_ptr {
-            unsafe {
-                prev.as_mut().next = Some(new_ptr);
-            }
-        } else {
-            match side {
-                Side::Buy => self.best_bid = Some(new_ptr),
-                Side::Sell => self.best_ask = Some(new_ptr),
+    }
+
+    /// Total quantity traded since the book was created.
+    ///
+    /// Only trades move it: cancels and modifies never do, and neither does
+    /// [`clear`](Self::clear). Saturates at `u64::MAX` rather than wrapping.
+    pub fn cumulative_volume(&self) -> Quantity {
+        self.cumulative_volume
+    }
+
+    /// Number of stop orders waiting for their trigger.
+    pub fn pending_stops(&self) -> usize {
+        self.stop_orders.len()
+    }
+
+    /// Submits every stop the last trade price has triggered, until none is
+    /// left. Each submission removes one stop, so this always terminates.
+    ///
+    /// With `into_trades` set, stop trades are matched straight into
+    /// `trades`, bypassing accumulation (see `place_limit_order_into`).
+    fn activate_stops(&mut self, trades: &mut Vec<Trade>, into_trades: bool) {
+        while let Some(last) = self.last_trade_price
+            && let Some(pos) = self.stop_orders.iter().position(|s| s.triggered_by(last))
+        {
+            let stop = self.stop_orders.remove(pos);
+            let req = OrderRequest::limit(stop.id.0, stop.side, stop.limit.0, stop.qty.0);
+            let caller_buf = if into_trades {
+                Some(&mut *trades)
+            } else {
+                None
+            };
+            match self.submit(req, caller_buf) {
+                Ok((_, _, stop_trades)) => trades.extend(stop_trades),
+                Err(_) => self.auto_cancelled.push(stop.id),
             }
         }
     }
 
-    pub fn best_ask_price(&self) -> Option<Price> {
-        self.best_ask.map(|ptr| unsafe { ptr.as_ref().price })
+    /// Restricts limit prices to multiples of `tick` (`0` or `1`, the
+    /// default, accepts any price).
+    ///
+    /// Off-grid prices on placement, modify and stop orders are rejected
+    /// with `OrderBookError::InvalidPrice` before any matching, so a bad
+    /// price never prints a trade. Orders already resting are not checked.
+    pub fn set_tick_size(&mut self, tick: u64) {
+        self.tick_size = tick;
     }
 
-    pub fn best_bid_price(&self) -> Option<Price> {
-        self.best_bid.map(|ptr| unsafe { ptr.as_ref().price })
+    pub fn tick_size(&self) -> u64 {
+        self.tick_size
     }
 
-    pub fn capacity_bytes(&self) -> usize {
-        self.order_arena.capacity()
+    /// Sets the lot size and min/max quantity for new orders.
+    ///
+    /// A placement (limit, market, IOC/FOK, stop) or a modify to a non-zero
+    /// quantity that breaks them is rejected with
+    /// `OrderBookError::InvalidQuantity` before any matching. Modifying to
+    /// `0` is still a cancel, and orders already resting are not checked.
+    pub fn set_quantity_limits(&mut self, limits: QuantityLimits) {
+        self.quantity_limits = limits;
     }
 
-    pub fn used_bytes(&self) -> usize {
-        self.order_arena.used_bytes()
+    pub fn quantity_limits(&self) -> QuantityLimits {
+        self.quantity_limits
     }
 
-    pub fn active_orders(&self) -> usize {
-        self.order_index.len()
+    /// `true` if `price` is on the tick grid (always, with no tick size).
+    #[inline]
+    fn on_tick(&self, price: u64) -> bool {
+        self.tick_size <= 1 || price.is_multiple_of(self.tick_size)
     }
 
-    pub fn free_slots(&self) -> usize {
-        self.free_list.len()
+    /// Rejects invalid fields, contradictory flags and post-only orders that
+    /// would take.
+    fn check_order_type(&self, req: &OrderRequest) -> Result<(), OrderBookError> {
+        if req.price.0 == 0 || !self.on_tick(req.price.0) {
+            return Err(OrderBookError::InvalidPrice);
+        }
+        if !self.quantity_limits.allows(req.qty.0) || req.display_qty > req.qty {
+            return Err(OrderBookError::InvalidQuantity);
+        }
+        if req.is_contradictory() {
+            return Err(OrderBookError::InvalidOrderType);
+        }
+        if req.post_only && self.would_cross(req.side, req.price) {
+            return Err(OrderBookError::PostOnlyWouldCross(req.id));
+        }
+        Ok(())
+    }
+
+    /// Registers `f` to be called with every trade the book executes,
+    /// replacing any previous observer.
+    ///
+    /// **Ordering:** `f` fires from inside the matching loop, once per trade,
+    /// in execution order (ascending `Trade::seq`), before the trade reaches
+    /// the returned `Vec`, the accumulator or the caller's buffer. The trades
+    /// are still delivered there as usual; the observer is an extra tap.
+    ///
+    /// Read-only queries (`simulate_order`, `vwap`, ...) never fire it.
+    pub fn on_trade<F: FnMut(&Trade) + Send + 'static>(&mut self, f: F) {
+        self.trade_observer = Some(Box::new(f));
+    }
+
+    /// Removes the trade observer, if any.
+    pub fn clear_trade_observer(&mut self) {
+        self.trade_observer = None;
+    }
+
+    /// Registers a clock that stamps each resting order's `placed_at`,
+    /// replacing any previous one.
+    ///
+    /// Without a clock, `placed_at` is the request's caller-supplied
+    /// `OrderRequest::timestamp` (`0` through the id/side/price/qty
+    /// convenience methods). With one, `f` is called once per order that
+    /// rests and its value wins. Either way the unit is the caller's: the book
+    /// only stores and subtracts it (see [`order_age`](Self::order_age)).
+    pub fn set_clock<F: FnMut() -> u64 + Send + 'static>(&mut self, f: F) {
+        self.clock = Some(Box::new(f));
+    }
+
+    /// Removes the clock; `placed_at` falls back to `OrderRequest::timestamp`.
+    pub fn clear_clock(&mut self) {
+        self.clock = None;
+    }
+
+    /// The `placed_at` stamp for `req`'s resting remainder.
+    #[inline]
+    fn placement_time(&mut self, req: &OrderRequest) -> u64 {
+        self.clock.as_mut().map_or(req.timestamp, |clock| clock())
+    }
+
+    /// How long order `id` has been resting as of `now`: `now - placed_at`,
+    /// in the clock's unit, or `None` if no such order rests.
+    ///
+    /// Amends keep `placed_at`, even those that lose queue priority; a
+    /// crossing amend is re-placed and restamped. A `now` before `placed_at`
+    /// gives `0`.
+    pub fn order_age(&self, id: u64, now: u64) -> Option<u64> {
+        let order = unsafe { self.order_index.get(&OrderId(id))?.as_ref() };
+        Some(now.saturating_sub(order.placed_at))
+    }
+
+    /// Enables (`Some`) or disables (`None`, the default) self-trade prevention
+    /// between orders carrying the same non-zero `OrderRequest::account`.
+    pub fn set_self_trade_prevention(&mut self, policy: Option<SelfTradePrevention>) {
+        self.self_trade_prevention = policy;
+    }
+
+    /// Selects FIFO (the default) or pro-rata matching. See [`MatchMode`].
+    ///
+    /// Applies to every later match, including the read-only dry runs
+    /// (`simulate_order`, `vwap`, ...) and the pre-checks built on them.
+    pub fn set_match_mode(&mut self, mode: MatchMode) {
+        self.match_mode = mode;
+    }
+
+    pub fn match_mode(&self) -> MatchMode {
+        self.match_mode
+    }
+
+    /// Selects maker-price (the default) or midpoint trade pricing. See
+    /// [`PricingPolicy`].
+    ///
+    /// Like the match mode, it also applies to `simulate_order` and
+    /// `preview_match`. `vwap` models a market order, which always trades at
+    /// the maker's price.
+    pub fn set_pricing_policy(&mut self, policy: PricingPolicy) {
+        self.pricing_policy = policy;
+    }
+
+    pub fn pricing_policy(&self) -> PricingPolicy {
+        self.pricing_policy
+    }
+
+    /// Sets the maker/taker fee rates the matcher stamps on each `Trade`.
+    /// See [`FeeSchedule::fees`] for units and rounding.
+    pub fn set_fee_schedule(&mut self, fees: FeeSchedule) {
+        self.fee_schedule = fees;
+    }
+
+    /// Turns the emergency kill switch on or off.
+    ///
+    /// While on, every placement (`place*`, `try_place`) and every modify is
+    /// rejected with `OrderBookError::KillSwitchActive` before any other check.
+    /// Cancels are always allowed so traders can flatten. Unlike a rate limit
+    /// this is an instantaneous operator override with no per-account state;
+    /// when off it costs a single predictable branch.
+    pub fn set_kill_switch(&mut self, on: bool) {
+        self.kill_switch = on;
+    }
+
+    pub fn kill_switch_active(&self) -> bool {
+        self.kill_switch
+    }
+
+    #[inline(always)]
+    fn check_kill_switch(&self) -> Result<(), OrderBookError> {
+        if self.kill_switch {
+            return Err(OrderBookError::KillSwitchActive);
+        }
+        Ok(())
+    }
+
+    /// Enables (`Some`) or disables (`None`) per-account order-entry throttling.
+    ///
+    /// Off by default. When enabled, every placement consumes one token from the
+    /// bucket of `OrderRequest::account`, refilled from `OrderRequest::timestamp`.
+    /// The book never reads a clock itself, so callers of the id/side/price/qty
+    /// convenience methods (account `0`, timestamp `0`) never see a refill and
+    /// should submit through [`place`](Self::place) instead.
+    pub fn set_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.rate_limiter = limit.map(RateLimiter::new);
+    }
+
+    /// Consumes a rate-limit token for the request's account, if throttling is on.
+    #[inline]
+    fn check_rate_limit(&mut self, req: &OrderRequest) -> Result<(), OrderBookError> {
+        if let Some(limiter) = self.rate_limiter.as_mut()
+            && !limiter.try_acquire(req.account, req.timestamp)
+        {
+            return Err(OrderBookError::RateLimited);
+        }
+        Ok(())
+    }
+
+    /// Appends a snapshot of the book's resting state to `buf`.
+    ///
+    /// **Format** (all integers little-endian):
+    /// - `u16` symbol length, symbol bytes, `u64` next sequence number,
+    ///   `u64` order count.
+    /// - Per order, bids then asks in priority order: `id: u64`, `side: u8`,
+    ///   `price: u64`, `qty: u64`, `display_qty: u64`, `total_qty: u64`,
+    ///   `seq: u64`, `placed_at: u64`, `expiry: u8 + u64` (tag `0` = none, `1` = some;
+    ///   the value is `0` when absent), `rest_mode: u8`, `account: u32`.
+    ///
+    /// Only order data is written, never arena bytes or pointers: the linked
+    /// list is rebuilt by [`deserialize`](Self::deserialize).
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        let symbol = self.symbol.as_bytes();
+        buf.reserve(2 + symbol.len() + 16 + self.order_index.len() * SNAPSHOT_ORDER_LEN);
+        buf.extend_from_slice(&(symbol.len() as u16).to_le_bytes());
+        buf.extend_from_slice(symbol);
+        buf.extend_from_slice(&self.seq_counter.to_le_bytes());
+        buf.extend_from_slice(&(self.order_index.len() as u64).to_le_bytes());
+
+        for order in self.iter_side(Side::Buy).chain(self.iter_side(Side::Sell)) {
+            buf.extend_from_slice(&order.id.0.to_le_bytes());
+            buf.push(order.side as u8);
+            buf.extend_from_slice(&order.price.0.to_le_bytes());
+            buf.extend_from_slice(&order.qty.0.to_le_bytes());
+            buf.extend_from_slice(&order.display_qty.0.to_le_bytes());
+            buf.extend_from_slice(&order.total_qty.0.to_le_bytes());
+            buf.extend_from_slice(&order.seq.to_le_bytes());
+            buf.extend_from_slice(&order.placed_at.to_le_bytes());
+            buf.push(order.expiry.is_some() as u8);
+            buf.extend_from_slice(&order.expiry.unwrap_or(0).to_le_bytes());
+            buf.push(order.rest_mode as u8);
+            buf.extend_from_slice(&order.account.to_le_bytes());
+        }
+    }
+
+    /// Rebuilds a book written by [`serialize`](Self::serialize) into a fresh
+    /// arena of `capacity` orders.
+    ///
+    /// Every order is re-inserted with its original `seq`, so priority (and
+    /// therefore depth, best prices and match order) is identical to the
+    /// source book. The sequence counter resumes where the source left off.
+    ///
+    /// Fails with `InvalidSnapshot` for malformed bytes or a different
+    /// symbol, `ArenaFull` if `capacity` is too small, and `DuplicateId` if an
+    /// id repeats.
+    pub fn deserialize(
+        symbol: &'static str,
+        capacity: usize,
+        bytes: &[u8],
+    ) -> Result<Self, OrderBookError> {
+        let mut reader = SnapshotReader(bytes);
+        let symbol_len = u16::from_le_bytes(reader.take()?) as usize;
+        if reader.bytes(symbol_len)? != symbol.as_bytes() {
+            return Err(OrderBookError::InvalidSnapshot);
+        }
+        let next_seq = u64::from_le_bytes(reader.take()?);
+        let order_count = u64::from_le_bytes(reader.take()?);
+
+        let mut book = Self::new(symbol, capacity);
+        for _ in 0..order_count {
+            let id = OrderId(u64::from_le_bytes(reader.take()?));
+            let side = match reader.take::<1>()?[0] {
+                0 => Side::Buy,
+                1 => Side::Sell,
+                _ => return Err(OrderBookError::InvalidSnapshot),
+            };
+            let price = Price(u64::from_le_bytes(reader.take()?));
+            let qty = Quantity(u64::from_le_bytes(reader.take()?));
+            let display_qty = Quantity(u64::from_le_bytes(reader.take()?));
+            let total_qty = Quantity(u64::from_le_bytes(reader.take()?));
+            if total_qty < qty {
+                return Err(OrderBookError::InvalidSnapshot);
+            }
+            let seq = u64::from_le_bytes(reader.take()?);
+            let placed_at = u64::from_le_bytes(reader.take()?);
+            let expiry_tag = reader.take::<1>()?[0];
+            let expiry_value = u64::from_le_bytes(reader.take()?);
+            let expiry = match expiry_tag {
+                0 => None,
+                1 => Some(expiry_value),
+                _ => return Err(OrderBookError::InvalidSnapshot),
+            };
+            let rest_mode = match reader.take::<1>()?[0] {
+                0 => RestMode::Normal,
+                1 => RestMode::CancelIfCrossed,
+                _ => return Err(OrderBookError::InvalidSnapshot),
+            };
+            let account = u32::from_le_bytes(reader.take()?);
+
+            book.restore_order(Order {
+                display_qty,
+                total_qty,
+                seq,
+                placed_at,
+                expiry,
+                rest_mode,
+                account,
+                ..Order::new(id, side, price, qty)
+            })?;
+        }
+
+        if !reader.0.is_empty() {
+            return Err(OrderBookError::InvalidSnapshot);
+        }
+        book.seq_counter = next_seq;
+        Ok(book)
+    }
+
+    /// Writes one CSV row per resting order, bids then asks in priority
+    /// order, after an `id,side,price,qty,seq` header.
+    ///
+    /// `side` is `buy` or `sell`; `price` and `qty` use the fixed-point
+    /// `Display` forms. Meant for offline analysis, so only those columns are
+    /// written: an iceberg appears as its visible slice, and expiry, rest
+    /// mode and account are dropped. Use [`serialize`](Self::serialize) for
+    /// a lossless copy.
+    #[cfg(feature = "std")]
+    pub fn to_csv<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "{CSV_HEADER}")?;
+        for order in self.iter_side(Side::Buy).chain(self.iter_side(Side::Sell)) {
+            let side = match order.side {
+                Side::Buy => "buy",
+                Side::Sell => "sell",
+            };
+            writeln!(
+                w,
+                "{},{side},{},{},{}",
+                order.id.0, order.price, order.qty, order.seq
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds a book from [`to_csv`](Self::to_csv) output into a fresh
+    /// arena of `capacity` orders.
+    ///
+    /// Orders keep their `seq`, so depth and priority match the dump, and
+    /// the sequence counter resumes after the highest `seq` read. Blank lines
+    /// are skipped.
+    ///
+    /// Fails with `InvalidCsvRow(line)` for a missing header, a read error,
+    /// or a row without exactly five valid fields (a zero price or quantity
+    /// counts as invalid), `DuplicateId` if an id repeats, and `ArenaFull`
+    /// if `capacity` is too small. Allocates per line; not for the hot path.
+    #[cfg(feature = "std")]
+    pub fn from_csv<R: BufRead>(
+        symbol: &'static str,
+        capacity: usize,
+        r: &mut R,
+    ) -> Result<Self, OrderBookError> {
+        let mut book = Self::new(symbol, capacity);
+        let mut lines = r.lines();
+        match lines.next() {
+            Some(Ok(header)) if header.trim_end() == CSV_HEADER => {}
+            _ => return Err(OrderBookError::InvalidCsvRow(1)),
+        }
+
+        let mut next_seq = 0;
+        for (index, line) in lines.enumerate() {
+            let line_no = index + 2;
+            let line = line.map_err(|_| OrderBookError::InvalidCsvRow(line_no))?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let order = parse_csv_row(line).ok_or(OrderBookError::InvalidCsvRow(line_no))?;
+            next_seq = order.seq.saturating_add(1).max(next_seq);
+            book.restore_order(order)?;
+        }
+
+        book.seq_counter = next_seq;
+        Ok(book)
+    }
+
+    /// Applies one journaled [`Command`] by dispatching to the matching entry
+    /// point, returning the trades it produced (a cancel never trades).
+    pub fn apply(&mut self, cmd: Command) -> Result<Vec<Trade>, OrderBookError> {
+        match cmd {
+            Command::Place {
+                id,
+                side,
+                price,
+                qty,
+            } => self
+                .place_limit_order(id.0, side, price.0, qty.0)
+                .map(|(_, trades)| trades),
+            Command::Cancel { id } => self.cancel_order(id.0).map(|_| Vec::new()),
+            Command::Modify { id, price, qty } => self
+                .modify_order(id.0, price.0, qty.0)
+                .map(|(_, trades)| trades),
+        }
+    }
+
+    /// Applies `cmds` all-or-nothing: if any command fails, every command
+    /// already applied in this call is undone and the error is returned.
+    /// On success, returns the trades the commands produced, in order.
+    ///
+    /// **Atomicity boundary:** trades cannot be rolled back, so nothing in a
+    /// transaction may trade. Places are submitted post-only and a modify
+    /// whose new price would cross the opposite side is refused; either
+    /// fails with `OrderBookError::PostOnlyWouldCross` (and rolls back the
+    /// transaction). Since no order matches, no stop can trigger either, and
+    /// the returned `Vec` is empty (and unallocated) unless that boundary is
+    /// ever widened; it mirrors [`apply`](Self::apply).
+    ///
+    /// **Rollback** walks an undo log in reverse: placed orders are removed,
+    /// and cancelled or modified orders are restored from a copy taken before
+    /// the command, **with their original `seq`**, so queue priority is
+    /// exactly as before. The sequence counter is rewound too. `version`
+    /// still advances, and rate-limit tokens spent are not refunded.
+    /// Metrics count the applied and undone operations as they happened.
+    pub fn transaction(&mut self, cmds: &[Command]) -> Result<Vec<Trade>, OrderBookError> {
+        let seq_at_start = self.seq_counter;
+        let mut undo_log = Vec::with_capacity(cmds.len());
+        let mut trades = Vec::new();
+
+        for &cmd in cmds {
+            match self.apply_undoable(cmd, &mut undo_log) {
+                Ok(mut cmd_trades) => trades.append(&mut cmd_trades),
+                Err(err) => {
+                    while let Some(undo) = undo_log.pop() {
+                        self.undo(undo);
+                    }
+                    self.seq_counter = seq_at_start;
+                    return Err(err);
+                }
+            }
+        }
+        Ok(trades)
+    }
+
+    /// Applies one non-trading [`transaction`](Self::transaction) command,
+    /// logging how to reverse it, and returns its trades.
+    fn apply_undoable(
+        &mut self,
+        cmd: Command,
+        undo_log: &mut Vec<Undo>,
+    ) -> Result<Vec<Trade>, OrderBookError> {
+        let trades = match cmd {
+            Command::Place {
+                id,
+                side,
+                price,
+                qty,
+            } => {
+                let (_, trades) = self.place(OrderRequest {
+                    post_only: true,
+                    ..OrderRequest::limit(id.0, side, price.0, qty.0)
+                })?;
+                undo_log.push(Undo::Placed(id));
+                trades
+            }
+            Command::Cancel { id } => {
+                if let Some(order) = self.pull_order(id) {
+                    self.metrics.orders_canceled += 1;
+                    undo_log.push(Undo::Resting(order));
+                } else {
+                    let pos = self
+                        .stop_orders
+                        .iter()
+                        .position(|s| s.id == id)
+                        .ok_or(OrderBookError::OrderNotFound(id))?;
+                    let stop = self.stop_orders.remove(pos);
+                    self.metrics.orders_canceled += 1;
+                    undo_log.push(Undo::Stop(pos, stop));
+                }
+                Vec::new()
+            }
+            Command::Modify { id, price, qty } => {
+                let before = match self.order_index.get(&id) {
+                    Some(ptr) => unsafe { ptr.as_ref() }.clone(),
+                    None => return Err(OrderBookError::OrderNotFound(id)),
+                };
+                if qty.0 != 0 && self.would_cross(before.side, price) {
+                    return Err(OrderBookError::PostOnlyWouldCross(id));
+                }
+                let (_, trades) = self.modify_order(id.0, price.0, qty.0)?;
+                undo_log.push(Undo::Resting(before));
+                trades
+            }
+        };
+        Ok(trades)
+    }
+
+    /// Reverses one logged transaction step.
+    fn undo(&mut self, undo: Undo) {
+        match undo {
+            Undo::Placed(id) => {
+                self.take_order(id);
+            }
+            Undo::Resting(order) => {
+                // Frees the slot the order is restored into (a cancelled
+                // order left its slot on the free list already).
+                self.take_order(order.id);
+                self.restore_order(order)
+                    .expect("rolled-back order fits its own slot");
+            }
+            Undo::Stop(pos, stop) => self.stop_orders.insert(pos, stop),
+        }
+    }
+
+    /// Rebuilds a book by applying `cmds` in order to a fresh book.
+    ///
+    /// Rejected commands are skipped, exactly as they were when first applied:
+    /// a reject leaves the book untouched. Replaying the same sequence always
+    /// yields byte-identical [`serialize`](Self::serialize) output.
+    pub fn replay(symbol: &'static str, capacity: usize, cmds: &[Command]) -> Self {
+        let mut book = Self::new(symbol, capacity);
+        for &cmd in cmds {
+            let _ = book.apply(cmd);
+        }
+        book
+    }
+
+    /// Faults in every page of the order arena (and the free-list buffer) up
+    /// front, so steady-state placement never touches a fresh page.
+    ///
+    /// Writes a placeholder order into each remaining arena slot. On a fresh
+    /// book the arena is then reset, so the bump allocator still starts from
+    /// the beginning; on a book already in use the remaining slots are handed
+    /// to the free list instead. Either way no resting order is affected.
+    ///
+    /// **Trade-off:** Costs startup time proportional to the arena size in
+    /// exchange for removing page-fault spikes from the first burst of
+    /// orders. Call it once, before trading starts.
+    pub fn prewarm(&mut self) {
+        let fresh = self.used_bytes() == 0 && self.free_list.is_empty();
+        let placeholder = Order::new(OrderId(0), Side::Buy, Price(0), Quantity(0));
+
+        while let Some(order_ptr) = self.alloc_from_arena(placeholder.clone()) {
+            self.free_list.push(order_ptr);
+        }
+
+        if fresh {
+            self.free_list.clear();
+            self.order_arenas.iter_mut().for_each(Arena::reset);
+        }
+    }
+
+    /// Grows order storage by `additional` slots without moving any order.
+    ///
+    /// A new arena chunk is appended rather than the existing one
+    /// reallocated, since every resting order is referenced by raw pointer
+    /// from the index and the intrusive list. **Every `OrderPtr` handed out
+    /// before a `reserve` stays valid after it.** The index, free list and
+    /// auto-cancel list are grown to match, so placements stay
+    /// allocation-free up to the new capacity.
+    ///
+    /// This allocates and is meant for an `ArenaFull` recovery path, not the
+    /// hot path. Fails with `ArenaFull`, leaving the book unchanged, if the
+    /// new size overflows or the allocator refuses the index or free list.
+    pub fn reserve(&mut self, additional: usize) -> Result<(), OrderBookError> {
+        if additional == 0 {
+            return Ok(());
+        }
+        let bytes = additional
+            .checked_mul(mem::size_of::<Order>())
+            .ok_or(OrderBookError::ArenaFull)?;
+        let slots = self.capacity_bytes() / mem::size_of::<Order>() + additional;
+        self.order_index
+            .try_reserve(slots.saturating_sub(self.order_index.len()))
+            .map_err(|_| OrderBookError::ArenaFull)?;
+        self.free_list
+            .try_reserve(slots.saturating_sub(self.free_list.len()))
+            .map_err(|_| OrderBookError::ArenaFull)?;
+        self.auto_cancelled
+            .try_reserve(slots.saturating_sub(self.auto_cancelled.len()))
+            .map_err(|_| OrderBookError::ArenaFull)?;
+
+        self.order_arenas.push(Arena::new(bytes));
+        Ok(())
+    }
+
+    /// Wipes every resting and parked stop order, keeping the arena's memory.
+    ///
+    /// The index and free list are emptied (both keep their capacity) and the
+    /// bump pointer of every arena chunk goes back to zero (chunks added by
+    /// `reserve` are kept), so the next placement reuses the first slot and
+    /// `used_bytes()` reads `0`. Every `OrderPtr` handed out before is
+    /// dangling afterwards. The last trade is forgotten; settings, the
+    /// sequence counter, cumulative volume and any accumulated trades are
+    /// kept.
+    pub fn clear(&mut self) {
+        self.order_index.clear();
+        self.free_list.clear();
+        self.order_arenas.iter_mut().for_each(Arena::reset);
+        self.levels = LevelIndex::default();
+        self.stop_orders.clear();
+        for group in &mut self.pegs {
+            group.pegs.clear();
+            group.reference = None;
+        }
+        self.last_trade_price = None;
+        self.last_trade_qty = None;
+
+        if self.best_bid.take().is_some() {
+            self.top_changes.0 += 1;
+        }
+        if self.best_ask.take().is_some() {
+            self.top_changes.1 += 1;
+        }
+        self.version += 1;
+    }
+
+    /// Returns `true` if a new order can be stored without exhausting the arena.
+    fn has_free_slot(&self) -> bool {
+        !self.free_list.is_empty() || self.order_arenas.iter().any(Arena::has_room)
+    }
+
+    /// Bump allocates from the first arena chunk with room for one more
+    /// order. Chunks fill in order, so this is almost always the first or
+    /// last one checked.
+    fn alloc_from_arena(&mut self, order: Order) -> Option<OrderPtr> {
+        self.order_arenas
+            .iter_mut()
+            .find(|arena| arena.has_room())?
+            .alloc(order)
+    }
+
+    /// Stores a fully formed order (e.g. read from a snapshot) at its
+    /// priority position, keeping its `seq`.
+    fn restore_order(&mut self, order: Order) -> Result<(), OrderBookError> {
+        let id = order.id;
+        if self.order_index.contains_key(&id) {
+            return Err(OrderBookError::DuplicateId(id));
+        }
+        let order_ptr = self
+            .try_alloc_slot(order)
+            .ok_or(OrderBookError::ArenaFull)?;
+        unsafe {
+            self.insert_sorted(order_ptr);
+        }
+        self.order_index.insert(id, order_ptr);
+        Ok(())
+    }
+
+    /// Slot allocation: free list first, then the arena. Returns `None` when
+    /// both are exhausted.
+    fn try_alloc_slot(&mut self, order_data: Order) -> Option<OrderPtr> {
+        let order_ptr = if let Some(mut recycled_ptr) = self.free_list.pop() {
+            self.metrics.free_list_reuses += 1;
+            unsafe {
+                *recycled_ptr.as_mut() = order_data;
+            }
+            recycled_ptr
+        } else {
+            self.alloc_from_arena(order_data)?
+        };
+
+        // Every caller indexes the order right after, so it is live now.
+        self.slot_allocations += 1;
+        self.peak_slots = self.peak_slots.max(self.order_index.len() + 1);
+        Some(order_ptr)
+    }
+
+    /// Dry run: returns the trades an order *would* generate right now.
+    ///
+    /// Runs the full crossing logic read-only (no maker is reduced or removed,
+    /// nothing rests, the arena is untouched). The returned trades carry
+    /// `OrderId(0)` as a placeholder `taker_id`.
+    pub fn simulate_order(&self, side: Side, price: u64, qty: u64) -> Vec<Trade> {
+        let (_, trades) = self.preview_match(side, price, qty);
+        trades
+    }
+
+    /// [`simulate_order`](Self::simulate_order) that also returns the
+    /// quantity left unfilled, for slippage checks before sending.
+    ///
+    /// Takes `&self`, so no maker quantity, link, head pointer or counter can
+    /// change. The walk shares its crossing, rest-mode, self-trade and
+    /// match-mode rules with the live matcher (see `matcher::walk_fills`),
+    /// so a preview followed at once by the real placement yields the same
+    /// fills, maker for maker and in the same order, re-queued iceberg
+    /// refills included.
+    /// `Trade::seq` is `0`, since a preview consumes no sequence numbers.
+    pub fn preview_match(&self, side: Side, price: u64, qty: u64) -> (Quantity, Vec<Trade>) {
+        matcher::simulate_match(self, OrderId(0), side, Price(price), Quantity(qty))
+    }
+
+    /// Average fill price of a hypothetical market order for `qty` on `side`,
+    /// rounded down. `None` if the book cannot fill all of it.
+    /// See [`matcher::vwap`].
+    pub fn vwap(&self, side: Side, qty: u64) -> Option<Price> {
+        matcher::vwap(self, side, Quantity(qty))
+    }
+
+    /// Opening-auction style uncross: matches a crossed or locked book at a
+    /// single clearing price. Returns that price (`None` if the book was not
+    /// crossed or locked, in which case nothing changes) and the trades.
+    ///
+    /// **Clearing price:** the resting price level that executes the most
+    /// volume; ties go to the smallest surplus (unmatched quantity on the
+    /// heavier side), then to the lowest price. Iceberg reserves count in
+    /// full.
+    ///
+    /// **Matching:** bids and asks are paired off in priority order, every
+    /// trade printing at the clearing price, until that volume has traded.
+    /// The later arrival of each pair is reported as the taker. Filled
+    /// orders are removed and partially filled ones keep their place
+    /// (iceberg slices refill as in continuous matching). Afterwards the
+    /// book is no longer crossed.
+    ///
+    /// Continuous placement never leaves the book crossed, so this applies
+    /// to books rebuilt with [`deserialize`](Self::deserialize) or
+    /// [`from_csv`](Self::from_csv). Rest modes and self-trade prevention
+    /// are not applied. The trades are always returned rather than
+    /// accumulated; any stop orders they trigger are then submitted as usual.
+    pub fn uncross(&mut self) -> (Option<Price>, Vec<Trade>) {
+        let (price, mut trades) = matcher::uncross(self);
+        if price.is_some() {
+            self.activate_stops(&mut trades, false);
+        }
+        (price, trades)
+    }
+
+    /// Sets how [`modify`](Self::modify) treats an id that is not resting.
+    pub fn set_modify_missing_policy(&mut self, policy: ModifyMissingPolicy) {
+        self.modify_missing_policy = policy;
+    }
+
+    /// Cancel/replace driven by a full [`OrderRequest`].
+    ///
+    /// If `req.id` is resting this behaves exactly like
+    /// [`modify_order`](Self::modify_order) (the resting order keeps its side;
+    /// `req.side` is ignored). If it is not, the outcome depends on the
+    /// [`ModifyMissingPolicy`]: `Reject` returns `OrderBookError::OrderNotFound`, while
+    /// `PlaceNew` places `req` as a fresh order, sparing clients that always
+    /// want their order live a reject → place round trip.
+    ///
+    /// [`modify_order`](Self::modify_order) itself always rejects a missing id,
+    /// since it carries no side to place a new order with.
+    pub fn modify(
+        &mut self,
+        req: OrderRequest,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        if self.order_index.contains_key(&req.id) {
+            return self.modify_order(req.id.0, req.price.0, req.qty.0);
+        }
+
+        match self.modify_missing_policy {
+            ModifyMissingPolicy::Reject => Err(OrderBookError::OrderNotFound(req.id)),
+            ModifyMissingPolicy::PlaceNew => self.place(req),
+        }
+    }
+
+    /// Modifies an existing order.
+    ///
+    /// **Quantity:** `new_qty` is what the order should hold from now on,
+    /// not its original size. It is compared with the quantity still
+    /// resting (`total_qty`, so fills since placement are already taken
+    /// out): an order placed for 10 and filled for 4 holds 6, and sending 10
+    /// would add 4 of fresh exposure. Any `new_qty` above the resting
+    /// quantity is therefore rejected with `OrderBookError::WouldIncreaseQty`
+    /// unless [`set_allow_modify_increase`](Self::set_allow_modify_increase)
+    /// is on. To move only the price, re-send the resting quantity.
+    ///
+    /// **Queue priority:**
+    /// - Same price, same quantity: a no-op, priority kept.
+    /// - Same price, lower quantity: reduced in place, priority kept (zero
+    ///   cancels the order).
+    /// - Same price, higher quantity (if allowed): re-queued at the back of
+    ///   the level, unless
+    ///   [`set_keep_priority_on_increase`](Self::set_keep_priority_on_increase)
+    ///   is on, in which case it grows in place.
+    /// - New price: re-queued at the back of the new level, or, if it would
+    ///   cross, cancelled and matched again as a fresh order.
+    ///
+    /// A crossing amend is all-or-nothing. If the fresh order is rejected
+    /// (e.g. `RateLimited`), the original is put back exactly as it was,
+    /// queue position included, and the error returned. If it fills
+    /// completely, nothing rests: the pointer is `None` and the id is no
+    /// longer in the book, so a later modify of it is `OrderNotFound`.
+    ///
+    /// To amend without any risk of re-queuing, use
+    /// [`modify_order_keep_priority`](Self::modify_order_keep_priority).
+    pub fn modify_order(
+        &mut self,
+        id: u64,
+        new_price: u64,
+        new_qty: u64,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.modify_order_ex(id, new_price, new_qty, false)
+    }
+
+    /// Reduces a resting order's quantity without ever re-queuing it.
+    ///
+    /// The price is unchanged and the order keeps its place in the queue.
+    /// `new_qty` above the current quantity (the full size, for an iceberg)
+    /// is rejected with `OrderBookError::WouldLosePriority` and the order is
+    /// left as it was; `0` cancels it and returns `None`.
+    pub fn modify_order_keep_priority(
+        &mut self,
+        id: u64,
+        new_qty: u64,
+    ) -> Result<Option<OrderPtr>, OrderBookError> {
+        let order_id = OrderId(id);
+        let order = match self.order_index.get(&order_id) {
+            Some(ptr) => unsafe { ptr.as_ref() },
+            None => return Err(OrderBookError::OrderNotFound(order_id)),
+        };
+        if new_qty > order.total_qty.0 {
+            return Err(OrderBookError::WouldLosePriority(order_id));
+        }
+        let price = order.price.0;
+        self.modify_order_ex(id, price, new_qty, true)
+            .map(|(order_ptr, _)| order_ptr)
+    }
+
+    /// Reduce-only amend: shrinks a resting order, never grows or reprices it.
+    ///
+    /// Like [`modify_order_keep_priority`](Self::modify_order_keep_priority),
+    /// but an increase is a risk violation rather than a priority concern, so
+    /// `new_qty` above the current quantity (the full size, for an iceberg) is
+    /// rejected with `OrderBookError::WouldIncreaseQty` even while
+    /// [`set_allow_modify_increase`](Self::set_allow_modify_increase) is on.
+    /// A reduction keeps the order's place in the queue; `0` cancels it and
+    /// returns `None`.
+    pub fn modify_reduce_only(
+        &mut self,
+        id: u64,
+        new_qty: u64,
+    ) -> Result<Option<OrderPtr>, OrderBookError> {
+        let order_id = OrderId(id);
+        let order = match self.order_index.get(&order_id) {
+            Some(ptr) => unsafe { ptr.as_ref() },
+            None => return Err(OrderBookError::OrderNotFound(order_id)),
+        };
+        if new_qty > order.total_qty.0 {
+            return Err(OrderBookError::WouldIncreaseQty(order_id));
+        }
+        let price = order.price.0;
+        self.modify_order_ex(id, price, new_qty, true)
+            .map(|(order_ptr, _)| order_ptr)
+    }
+
+    /// Lets a modify raise an order above its resting quantity (off by
+    /// default, so an amend can never add exposure; see
+    /// [`modify_order`](Self::modify_order)).
+    pub fn set_allow_modify_increase(&mut self, on: bool) {
+        self.allow_modify_increase = on;
+    }
+
+    /// Lets a same-price quantity increase keep queue priority instead of
+    /// re-queuing (off by default). Some venues allow this; most do not.
+    /// Increases must also be enabled with
+    /// [`set_allow_modify_increase`](Self::set_allow_modify_increase).
+    ///
+    /// With it on, such an amend grows the order in place (an iceberg's
+    /// hidden reserve grows; its visible slice is unchanged). Price changes
+    /// still re-queue.
+    pub fn set_keep_priority_on_increase(&mut self, on: bool) {
+        self.keep_priority_on_increase = on;
+    }
+
+    /// Modifies an existing order, optionally refusing to lose queue priority.
+    ///
+    /// With `reject_if_priority_lost` set, any amendment that would take the
+    /// slow path (a price change or a quantity increase) is rejected with
+    /// `OrderBookError::WouldLosePriority` and the order is left exactly as it
+    /// was. No-ops, quantity reductions and reductions to zero (a cancel) are
+    /// still applied, as is a same-price increase while
+    /// [`set_keep_priority_on_increase`](Self::set_keep_priority_on_increase)
+    /// is on. Increases are subject to the same exposure guard as
+    /// [`modify_order`](Self::modify_order) either way.
+    pub fn modify_order_ex(
+        &mut self,
+        id: u64,
+        new_price: u64,
+        new_qty: u64,
+        reject_if_priority_lost: bool,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.check_kill_switch()?;
+
+        if new_price == 0 || !self.on_tick(new_price) {
+            return Err(OrderBookError::InvalidPrice);
+        }
+        if new_qty != 0 && !self.quantity_limits.allows(new_qty) {
+            return Err(OrderBookError::InvalidQuantity);
+        }
+
+        let order_id = OrderId(id);
+        let new_price = Price(new_price);
+        let new_qty = Quantity(new_qty);
+
+        let mut order_ptr = match self.order_index.get(&order_id) {
+            Some(ptr) => *ptr,
+            None => return Err(OrderBookError::OrderNotFound(order_id)),
+        };
+
+        // Safety: We hold mutable reference to book
+        let order = unsafe { order_ptr.as_mut() };
+
+        // NO-OP: Clients re-send identical values. Keep queue position and
+        // leave the index, free list and `version` untouched, so the amend is
+        // idempotent and depth subscribers see no change.
+        // Quantities are compared against `total_qty`: an iceberg is amended
+        // by its full size, not its visible slice.
+        if order.price == new_price && order.total_qty == new_qty {
+            return Ok((Some(order_ptr), vec![]));
+        }
+
+        // EXPOSURE GUARD: `total_qty` already excludes any fills, so this
+        // also catches re-sending the original size of a part-filled order.
+        if new_qty > order.total_qty && !self.allow_modify_increase {
+            return Err(OrderBookError::WouldIncreaseQty(order_id));
+        }
+
+        // FAST PATH: Price match + Qty reduction
+        if order.price == new_price && new_qty < order.total_qty {
+            order.qty = order.qty.min(new_qty);
+            order.total_qty = new_qty;
+            self.version += 1;
+            if new_qty.0 == 0 {
+                self.cancel_order(id)?;
+                return Ok((None, vec![]));
+            }
+            self.metrics.orders_modified += 1;
+            return Ok((Some(order_ptr), vec![]));
+        }
+
+        // IN-PLACE INCREASE: Only where the venue grants it (opt-in).
+        if order.price == new_price && self.keep_priority_on_increase {
+            if !order.is_iceberg() {
+                order.qty = new_qty;
+            }
+            order.total_qty = new_qty;
+            self.version += 1;
+            self.metrics.orders_modified += 1;
+            return Ok((Some(order_ptr), vec![]));
+        }
+
+        // SLOW PATH: Price change or Qty increase -> Loss of Priority
+        if reject_if_priority_lost {
+            return Err(OrderBookError::WouldLosePriority(order_id));
+        }
+        let side = order.side;
+
+        // Non-crossing move: re-queue in place (same slot, same index entry).
+        if new_qty.0 > 0 && !self.would_cross(side, new_price) {
+            let slice = order.slice_of(new_qty);
+            order.total_qty = new_qty;
+            unsafe {
+                self.relink(order_ptr, new_price, slice);
+            }
+            self.metrics.orders_modified += 1;
+            return Ok((Some(order_ptr), vec![]));
+        }
+
+        // Crossing move: the order must go through matching again.
+        // Every rejection `place` can return happens before it matches or
+        // rests anything, so on error the book is exactly as `pull_order`
+        // left it and putting the original back undoes the amend. Its slot is
+        // the one just freed, so the restore cannot run out of room.
+        let original = self
+            .pull_order(order_id)
+            .expect("order is indexed: looked up above");
+        let placed = self.place(OrderRequest {
+            rest_mode: original.rest_mode,
+            account: original.account,
+            expiry: original.expiry,
+            display_qty: original.display_qty.min(new_qty),
+            ..OrderRequest::limit(id, side, new_price.0, new_qty.0)
+        });
+        match placed {
+            Ok(_) => self.metrics.orders_modified += 1,
+            Err(_) => self
+                .restore_order(original)
+                .expect("the pulled order's slot is free again"),
+        }
+        placed
+    }
+
+    /// Returns `true` if an order on `side` at `price` would match the
+    /// current best order on the opposite side.
+    fn would_cross(&self, side: Side, price: Price) -> bool {
+        match side {
+            Side::Buy => self.best_ask_price().is_some_and(|ask| price >= ask),
+            Side::Sell => self.best_bid_price().is_some_and(|bid| price <= bid),
+        }
+    }
+
+    /// Moves a resting order to a new price/visible quantity without leaving
+    /// its slot. `total_qty` is left to the caller.
+    ///
+    /// The node is unlinked and re-inserted at the back of its new price level
+    /// (time priority is lost), but it keeps the same arena memory and the same
+    /// `order_index` entry. No free-list round trip, no HashMap remove/insert.
+    /// The matcher uses this to re-queue a refilled iceberg slice.
+    ///
+    /// # Safety
+    /// Only valid when the order **cannot cross** at `new_price` (see
+    /// `would_cross`): a relinked order never enters the matcher, so calling this
+    /// for a crossing price would leave a crossed book. Crossing amendments must
+    /// fall back to cancel + `place_limit_order` so the order is matched first.
+    pub(crate) unsafe fn relink(&mut self, mut ptr: OrderPtr, new_price: Price, new_qty: Quantity) {
+        self.remove_order(ptr);
+
+        let seq = self.take_seq();
+        unsafe {
+            let order = ptr.as_mut();
+            order.price = new_price;
+            order.qty = new_qty;
+            order.seq = seq;
+        }
+
+        unsafe {
+            self.insert_sorted(ptr);
+        }
+    }
+
+    /// Cancels a resting order, or a parked stop order with this id.
+    ///
+    /// Returns the id with the quantity that was still open when it was
+    /// pulled: whatever partial fills since placement left, including an
+    /// iceberg's hidden reserve. For reconciliation, that is exactly the
+    /// position the cancel released.
+    pub fn cancel_order(&mut self, id: u64) -> Result<(OrderId, Quantity), OrderBookError> {
+        let order_id = OrderId(id);
+        if let Some(order) = self.pull_order(order_id) {
+            self.metrics.orders_canceled += 1;
+            return Ok((order_id, order.total_qty));
+        }
+        match self.stop_orders.iter().position(|s| s.id == order_id) {
+            Some(pos) => {
+                let stop = self.stop_orders.remove(pos);
+                self.metrics.orders_canceled += 1;
+                Ok((order_id, stop.qty))
+            }
+            None => Err(OrderBookError::OrderNotFound(order_id)),
+        }
+    }
+
+    /// Switches between per-call trade returns and a book-owned accumulator.
+    ///
+    /// While accumulation is on, [`place`](Self::place) and every method built
+    /// on it return an empty trade `Vec` (which does not allocate) and append
+    /// the trades to an internal buffer instead, to be consumed in batch with
+    /// [`drain_trades`](Self::drain_trades). `try_place` keeps using the
+    /// caller's buffer.
+    ///
+    /// **Memory Growth:** An `Unbounded` accumulator grows until drained.
+    /// `Capped(n)` reserves `n` trades up front and rejects (with
+    /// `OrderBookError::TradeBufferFull`, before any state changes) any placement
+    /// whose trades would not fit, so it never reallocates. Switching modes
+    /// keeps trades that are already buffered.
+    pub fn set_trade_accumulation(&mut self, mode: TradeAccumulation) {
+        if let TradeAccumulation::Capped(cap) = mode {
+            self.trade_accumulator
+                .reserve(cap.saturating_sub(self.trade_accumulator.len()));
+        }
+        self.trade_accumulation = mode;
+    }
+
+    /// Removes and yields every accumulated trade, in execution order.
+    ///
+    /// The buffer keeps its capacity, so draining never frees memory.
+    pub fn drain_trades(&mut self) -> alloc::vec::Drain<'_, Trade> {
+        self.trade_accumulator.drain(..)
+    }
+
+    /// Drains the ids of resting orders the book pulled on its own.
+    ///
+    /// A [`RestMode::CancelIfCrossed`](crate::storage::layout::RestMode) order is cancelled at the exact moment an
+    /// incoming opposite-side order's matching walk reaches it, i.e. when the
+    /// aggressive price touches or crosses the resting price and every order
+    /// ahead of it in priority has already been matched. Makers removed by
+    /// [`SelfTradePrevention`] are reported here too. Neither produces a
+    /// `Trade`, so callers using these features should drain this after each
+    /// placement.
+    pub fn drain_auto_cancelled(&mut self) -> alloc::vec::Drain<'_, OrderId> {
+        self.auto_cancelled.drain(..)
+    }
+
+    /// Cancels every resting order on `side` ("pull my quotes") and returns
+    /// their ids in priority order. The opposite side is untouched.
+    ///
+    /// Each slot is recycled exactly as in [`cancel_order`](Self::cancel_order).
+    pub fn cancel_side(&mut self, side: Side) -> Vec<OrderId> {
+        let mut cancelled = Vec::with_capacity(self.order_index.len());
+        let mut current_ptr = match side {
+            Side::Buy => self.best_bid,
+            Side::Sell => self.best_ask,
+        };
+
+        while let Some(ptr) = current_ptr {
+            // Capture `next` first: unlinking clears the node's pointers.
+            let order = unsafe { ptr.as_ref() };
+            current_ptr = order.next;
+            let order_id = order.id;
+            self.take_order(order_id);
+            cancelled.push(order_id);
+        }
+        self.metrics.orders_canceled += cancelled.len() as u64;
+
+        debug_assert!(self.iter_side(side).next().is_none());
+        cancelled
+    }
+
+    /// Cancels every resting order matching `pred` and returns their ids,
+    /// bids best-to-worst, then asks.
+    ///
+    /// One walk per side: each order is tested once and, on a match, unlinked,
+    /// dropped from the index and its slot recycled exactly as in
+    /// [`cancel_order`](Self::cancel_order). `pred` sees the whole [`Order`]
+    /// (full size, account, expiry, ...). Cancelling never changes an order
+    /// that has not been visited yet, so the result is the same as testing
+    /// the book as it was on entry. Allocates the returned `Vec`; intended
+    /// for maintenance, not the hot path.
+    pub fn cancel_where<F: Fn(&Order) -> bool>(&mut self, pred: F) -> Vec<OrderId> {
+        let mut cancelled = Vec::new();
+        for side in [Side::Buy, Side::Sell] {
+            let mut current_ptr = match side {
+                Side::Buy => self.best_bid,
+                Side::Sell => self.best_ask,
+            };
+            while let Some(ptr) = current_ptr {
+                // Capture `next` first: unlinking clears the node's pointers.
+                let order = unsafe { ptr.as_ref() };
+                current_ptr = order.next;
+                if pred(order) {
+                    let order_id = order.id;
+                    self.take_order(order_id);
+                    cancelled.push(order_id);
+                }
+            }
+        }
+        self.metrics.orders_canceled += cancelled.len() as u64;
+        cancelled
+    }
+
+    /// Cancels every resting order whose `expiry` is at or before `now`, and
+    /// returns their ids (bids best-to-worst, then asks).
+    ///
+    /// The book never reads a clock: `now` is in whatever unit the caller set
+    /// `OrderRequest::expiry` in. Each expired order is removed through
+    /// [`cancel_order`](Self::cancel_order), so the index and free list stay
+    /// consistent.
+    ///
+    /// **Complexity:** O(N) over every resting order, since nothing is
+    /// indexed by time. The sides are walked rather than `order_index`, so
+    /// the result does not depend on hash order. If sweeps become hot, a
+    /// min-heap of `(expiry, OrderId)` filled at placement would make this
+    /// O(expired * log N); stale heap entries for orders already gone would
+    /// simply be skipped.
+    pub fn expire_orders(&mut self, now: u64) -> Vec<OrderId> {
+        let expired: Vec<OrderId> = self
+            .iter_side(Side::Buy)
+            .chain(self.iter_side(Side::Sell))
+            .filter(|order| order.expiry.is_some_and(|expiry| expiry <= now))
+            .map(|order| order.id)
+            .collect();
+
+        for &order_id in &expired {
+            let _ = self.cancel_order(order_id.0);
+        }
+        expired
+    }
+
+    /// Cancels an order and returns an owned copy of everything it held.
+    ///
+    /// The slot is recycled exactly as in [`cancel_order`](Self::cancel_order);
+    /// the returned [`OrderView`] is a copy, not a pointer into the arena, so it
+    /// stays valid after the slot is reused. This is the one-call way to pull
+    /// an order out of this book and route it elsewhere.
+    ///
+    /// Returns `None` if no order with `id` is resting.
+    pub fn cancel_and_take(&mut self, id: u64) -> Option<OrderView> {
+        let view = self.take_order(OrderId(id))?;
+        self.metrics.orders_canceled += 1;
+        Some(view)
+    }
+
+    /// Removes an order from the index and the book, recycling its slot.
+    ///
+    /// The order's data is copied out **before** the pointer goes on the free
+    /// list, since the next placement may overwrite it.
+    pub(crate) fn take_order(&mut self, order_id: OrderId) -> Option<OrderView> {
+        self.pull_order(order_id)
+            .map(|order| OrderView::from(&order))
+    }
+
+    /// [`take_order`](Self::take_order), returning the full order data.
+    fn pull_order(&mut self, order_id: OrderId) -> Option<Order> {
+        let order_ptr = self.order_index.remove(&order_id)?;
+        let order = unsafe { order_ptr.as_ref() }.clone();
+
+        // 1. O(1) Unlink
+        self.remove_order(order_ptr);
+
+        // 2. O(1) Recycle: Push the pointer onto the free list stack
+        self.free_list.push(order_ptr);
+
+        Some(order)
+    }
+
+    pub(crate) fn remove_order(&mut self, mut ptr: OrderPtr) {
+        self.version += 1;
+        unsafe {
+            let order = ptr.as_mut();
+            let next_ptr = order.next;
+            let prev_ptr = order.prev;
+
+            self.levels.unlink(order.side, order.price, ptr, prev_ptr);
+
+            if let Some(mut next) = next_ptr {
+                next.as_mut().prev = prev_ptr;
+            }
+
+            if let Some(mut prev) = prev_ptr {
+                prev.as_mut().next = next_ptr;
+            }
+
+            if self.best_bid == Some(ptr) {
+                self.best_bid = next_ptr;
+                self.top_changes.0 += 1;
+            }
+            if self.best_ask == Some(ptr) {
+                self.best_ask = next_ptr;
+                self.top_changes.1 += 1;
+            }
+
+            order.next = None;
+            order.prev = None;
+        }
+    }
+
+    /// Links a node into its side at its priority position.
+    ///
+    /// **Ordering Key:** [`compare_priority`]: price (better first), then
+    /// `seq` (lower first), then `OrderId` (lower first). The key is a total
+    /// order, so the resulting list never depends on insertion history, even
+    /// if two orders share a `seq` (e.g. after a snapshot restore).
+    unsafe fn insert_sorted(&mut self, mut new_ptr: OrderPtr) {
+        self.version += 1;
+        let side = unsafe { new_ptr.as_ref().side };
+
+        // LEVEL LOOKUP (O(log L)): the tail of this price level, or of the
+        // next-better level if this price is new. No walk over orders.
+        let price = unsafe { new_ptr.as_ref().price };
+        let level_tail = self.levels.predecessor(side, price);
+        let current_ptr = match level_tail {
+            Some(prev) => unsafe { prev.as_ref().next },
+            None => match side {
+                Side::Buy => self.best_bid,
+                Side::Sell => self.best_ask,
+            },
+        };
+        let (prev_ptr, current_ptr) = unsafe { Self::tie_break(new_ptr, level_tail, current_ptr) };
+        // Only a node linked behind the old tail becomes the new tail.
+        if prev_ptr == level_tail {
+            self.levels.push_tail(side, price, new_ptr);
+        }
+
+        unsafe {
+            new_ptr.as_mut().next = current_ptr;
+        }
+        unsafe {
+            new_ptr.as_mut().prev = prev_ptr;
+        }
+
+        if let Some(mut curr) = current_ptr {
+            unsafe {
+                curr.as_mut().prev = Some(new_ptr);
+            }
+        }
+        if let Some(mut prev) = prev_ptr {
+            unsafe {
+                prev.as_mut().next = Some(new_ptr);
+            }
+        } else {
+            match side {
+                Side::Buy => {
+                    self.best_bid = Some(new_ptr);
+                    self.top_changes.0 += 1;
+                }
+                Side::Sell => {
+                    self.best_ask = Some(new_ptr);
+                    self.top_changes.1 += 1;
+                }
+            }
+        }
+    }
+
+    /// Moves a tail-of-level position forward past any orders that rank after
+    /// `new_ptr` (see [`compare_priority`]).
+    ///
+    /// Sequence numbers are normally issued in insertion order, so the loop
+    /// exits on its first check and appending stays O(1).
+    ///
+    /// # Safety
+    /// `prev`/`next` must be adjacent nodes (or list ends) with `prev` being the
+    /// last node at or better than the new order's price.
+    unsafe fn tie_break(
+        new_ptr: OrderPtr,
+        mut prev: Option<OrderPtr>,
+        mut next: Option<OrderPtr>,
+    ) -> (Option<OrderPtr>, Option<OrderPtr>) {
+        let new_order = OrderView::from(unsafe { new_ptr.as_ref() });
+
+        while let Some(p) = prev {
+            let prev_order = unsafe { p.as_ref() };
+            if compare_priority(&new_order, &OrderView::from(prev_order)).is_ge() {
+                break;
+            }
+            next = Some(p);
+            prev = prev_order.prev;
+        }
+
+        (prev, next)
+    }
+
+    /// Both top-of-book prices as `(best_bid, best_ask)` in one call.
+    ///
+    /// A pure read: two head-pointer loads, no allocation. Prefer this over
+    /// `best_bid_price()` + `best_ask_price()` in quoting loops that read the
+    /// spread on every tick.
+    #[inline]
+    pub fn best_prices(&self) -> (Option<Price>, Option<Price>) {
+        let bid = self.best_bid.map(|ptr| unsafe { ptr.as_ref().price });
+        let ask = self.best_ask.map(|ptr| unsafe { ptr.as_ref().price });
+        (bid, ask)
+    }
+
+    /// `best_ask - best_bid`, or `None` if either side is empty.
+    ///
+    /// The matcher never leaves the book crossed, so this is normally
+    /// positive. A book restored crossed (see [`is_crossed`](Self::is_crossed))
+    /// has no meaningful spread and also returns `None`; a locked book
+    /// returns `Some(0)`.
+    pub fn spread(&self) -> Option<Price> {
+        let (Some(bid), Some(ask)) = self.best_prices() else {
+            return None;
+        };
+        ask.0.checked_sub(bid.0).map(Price)
+    }
+
+    /// `true` if the best bid is at or through the best ask. A locked book
+    /// (`bid == ask`) counts as crossed; an empty side never does.
+    ///
+    /// The matcher never produces this. A book rebuilt from a snapshot or CSV
+    /// may hold it until [`uncross`](Self::uncross) runs.
+    #[inline]
+    pub fn is_crossed(&self) -> bool {
+        matches!(self.best_prices(), (Some(bid), Some(ask)) if bid >= ask)
+    }
+
+    /// Midpoint of the best bid and ask, or `None` if either side is empty.
+    ///
+    /// An odd sum rounds **down** to the nearest raw price unit, so the result
+    /// may fall between ticks. Computed in `u128`, so it cannot overflow.
+    pub fn mid_price(&self) -> Option<Price> {
+        let (Some(bid), Some(ask)) = self.best_prices() else {
+            return None;
+        };
+        Some(Price(((bid.0 as u128 + ask.0 as u128) / 2) as u64))
+    }
+
+    /// Order-book imbalance over the top `levels` aggregated price levels per
+    /// side: `(bid_vol - ask_vol) / (bid_vol + ask_vol)`, in `[-1, 1]`.
+    ///
+    /// Positive means more resting size to buy than to sell. Levels are
+    /// aggregated exactly as in [`depth`](Self::depth) (visible quantity
+    /// only). Returns `None` if either side is empty or `levels` is `0`.
+    ///
+    /// A floating-point convenience for signal generation: the volumes are
+    /// summed exactly in integers and only the final ratio is an `f64`.
+    /// Allocates the two ladders; not for the matching path.
+    pub fn imbalance(&self, levels: usize) -> Option<f64> {
+        let volume = |side| -> u128 {
+            self.side_depth(side, levels)
+                .iter()
+                .map(|(_, qty)| qty.0 as u128)
+                .sum()
+        };
+        let (bid_vol, ask_vol) = (volume(Side::Buy), volume(Side::Sell));
+        if bid_vol == 0 || ask_vol == 0 {
+            return None;
+        }
+        Some((bid_vol as f64 - ask_vol as f64) / (bid_vol + ask_vol) as f64)
+    }
+
+    pub fn best_ask_price(&self) -> Option<Price> {
+        self.best_ask.map(|ptr| unsafe { ptr.as_ref().price })
+    }
+
+    pub fn best_bid_price(&self) -> Option<Price> {
+        self.best_bid.map(|ptr| unsafe { ptr.as_ref().price })
+    }
+
+    /// Aggregated L2 view of the top `levels` price points on each side.
+    ///
+    /// Orders resting at the same price are summed into a single entry.
+    /// Bids are returned best (highest) first, asks best (lowest) first.
+    pub fn depth(&self, levels: usize) -> Depth {
+        Depth {
+            bids: self.side_depth(Side::Buy, levels),
+            asks: self.side_depth(Side::Sell, levels),
+        }
+    }
+
+    /// [`depth`](Self::depth) as a `(bids, asks)` tuple, for callers that
+    /// don't want the [`Depth`] wrapper. Either ladder is shorter than
+    /// `levels` if the side has fewer distinct prices.
+    pub fn depth_snapshot(&self, levels: usize) -> (Ladder, Ladder) {
+        let Depth { bids, asks } = self.depth(levels);
+        (bids, asks)
+    }
+
+    /// Number of distinct price levels resting on `side` (`0` if empty).
+    ///
+    /// O(1): read from the price-level index rather than counted by walking
+    /// the orders.
+    pub fn level_count(&self, side: Side) -> usize {
+        self.levels.level_count(side)
+    }
+
+    /// A point-in-time copy of every aggregated level on both sides, safe to
+    /// share with reader threads while this book keeps changing.
+    ///
+    /// Allocates one `Vec` per side, sized to the side's level count.
+    pub fn snapshot(&self) -> BookSnapshot {
+        BookSnapshot::new(
+            self.version,
+            self.side_depth(Side::Buy, self.level_count(Side::Buy)),
+            self.side_depth(Side::Sell, self.level_count(Side::Sell)),
+        )
+    }
+
+    /// Aggregated `(Price, Quantity)` ladder for a single side, best price first.
+    /// Level sizes saturate at `u64::MAX`.
+    pub fn side_depth(&self, side: Side, levels: usize) -> Vec<(Price, Quantity)> {
+        let mut ladder: Vec<(Price, Quantity)> = Vec::with_capacity(levels);
+        let mut current_ptr = match side {
+            Side::Buy => self.best_bid,
+            Side::Sell => self.best_ask,
+        };
+
+        while let Some(ptr) = current_ptr {
+            let order = unsafe { ptr.as_ref() };
+            match ladder.last_mut() {
+                Some((price, qty)) if *price == order.price => {
+                    qty.0 = qty.0.saturating_add(order.qty.0);
+                }
+                _ => {
+                    if ladder.len() == levels {
+                        break;
+                    }
+                    ladder.push((order.price, order.qty));
+                }
+            }
+            current_ptr = order.next;
+        }
+
+        ladder
+    }
+
+    /// [`side_depth`](Self::side_depth) into a stack array: the top `N`
+    /// aggregated levels, best first, and how many were filled.
+    ///
+    /// Slots past the count are `(Price(0), Quantity(0))`. Level sizes
+    /// saturate at `u64::MAX`. Never touches the heap, so a top-of-book feed
+    /// can publish the best 5 or 10 levels on every update.
+    pub fn top_levels<const N: usize>(&self, side: Side) -> ([(Price, Quantity); N], usize) {
+        let mut ladder = [(Price(0), Quantity(0)); N];
+        let mut filled = 0;
+
+        for order in self.iter_side(side) {
+            if filled > 0 && ladder[filled - 1].0 == order.price {
+                let size = &mut ladder[filled - 1].1.0;
+                *size = size.saturating_add(order.qty.0);
+                continue;
+            }
+            if filled == N {
+                break;
+            }
+            ladder[filled] = (order.price, order.qty);
+            filled += 1;
+        }
+
+        (ladder, filled)
+    }
+
+    /// Checks the book's structural invariants, describing the first
+    /// violation found.
+    ///
+    /// Walks both sides from the head and verifies that:
+    /// - every order is strictly behind its predecessor in priority (so
+    ///   bids descend and asks ascend in price) and sits on its own side;
+    /// - `next`/`prev` links are symmetric (`a.next.prev == a`);
+    /// - every linked order is in `order_index` at that slot, and the index
+    ///   holds nothing that is not linked (so no order is on both sides);
+    /// - each visible quantity is in `1..=total_qty`;
+    /// - the level index holds exactly the list's levels, each mapped to its
+    ///   last order.
+    ///
+    /// O(N) and allocates on failure. Meant for tests and
+    /// `debug_assertions` builds while extending the matcher, not for the
+    /// hot path. Needs the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn validate(&self) -> Result<(), String> {
+        let linked = self.validate_side(Side::Buy)? + self.validate_side(Side::Sell)?;
+        if linked != self.order_index.len() {
+            return Err(format!(
+                "{linked} orders are linked but order_index holds {}",
+                self.order_index.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// [`validate`](Self::validate) for one side; returns its order count.
+    #[cfg(feature = "std")]
+    fn validate_side(&self, side: Side) -> Result<usize, String> {
+        let mut prev: Option<OrderPtr> = None;
+        let mut current = match side {
+            Side::Buy => self.best_bid,
+            Side::Sell => self.best_ask,
+        };
+        let (mut count, mut levels) = (0, 0);
+
+        while let Some(ptr) = current {
+            // More nodes than index entries means a cycle or a stray node;
+            // stop before walking it forever.
+            if count == self.order_index.len() {
+                return Err(format!(
+                    "{side:?} side links more orders than order_index holds ({count})"
+                ));
+            }
+            let order = unsafe { ptr.as_ref() };
+            let id = order.id.0;
+
+            if order.side != side {
+                return Err(format!(
+                    "order {id} is a {:?} order linked on the {side:?} side",
+                    order.side
+                ));
+            }
+            if order.prev != prev {
+                return Err(format!(
+                    "order {id}: prev does not point back to the order linked before it"
+                ));
+            }
+            match self.order_index.get(&order.id) {
+                Some(&indexed) if indexed == ptr => {}
+                Some(_) => return Err(format!("order {id}: order_index points at another slot")),
+                None => return Err(format!("order {id} is linked but missing from order_index")),
+            }
+            if order.qty.0 == 0 || order.qty > order.total_qty {
+                return Err(format!(
+                    "order {id}: visible qty {} outside 1..={}",
+                    order.qty.0, order.total_qty.0
+                ));
+            }
+            if let Some(prev_ptr) = prev {
+                let prev_order = unsafe { prev_ptr.as_ref() };
+                if compare_priority(&OrderView::from(prev_order), &OrderView::from(order))
+                    != Ordering::Less
+                {
+                    return Err(format!(
+                        "order {id} is linked after order {} but does not rank behind it",
+                        prev_order.id.0
+                    ));
+                }
+            }
+
+            let ends_level = order
+                .next
+                .is_none_or(|next| unsafe { next.as_ref() }.price != order.price);
+            if ends_level {
+                levels += 1;
+                if self.levels.tail(side, order.price) != Some(ptr) {
+                    return Err(format!(
+                        "{side:?} level {} does not map to its last order {id}",
+                        order.price.0
+                    ));
+                }
+            }
+
+            prev = current;
+            current = order.next;
+            count += 1;
+        }
+
+        let indexed_levels = self.levels.level_count(side);
+        if levels != indexed_levels {
+            return Err(format!(
+                "{side:?} side has {levels} price levels but the level index holds {indexed_levels}"
+            ));
+        }
+        Ok(count)
+    }
+
+    /// Iterates every resting order on a side in priority order (best first).
+    ///
+    /// Yields shared references into the arena, tied to the book's borrow, so
+    /// the iterator cannot outlive (or observe) a mutation.
+    pub fn iter_side(&self, side: Side) -> OrderIter<'_> {
+        OrderIter {
+            current: match side {
+                Side::Buy => self.best_bid,
+                Side::Sell => self.best_ask,
+            },
+            _book: PhantomData,
+        }
+    }
+
+    /// [`iter_side`](Self::iter_side) starting at the first order priced at
+    /// `price` or worse (at or below it for bids, at or above for asks), then
+    /// outward to the worst level. Empty if nothing rests that deep.
+    ///
+    /// The start is found through the price-level index in O(log L) for L
+    /// levels, so paging through a deep ladder never re-walks the levels
+    /// above the page.
+    pub fn iter_from(&self, side: Side, price: u64) -> OrderIter<'_> {
+        let current = match self.levels.better_tail(side, Price(price)) {
+            // SAFETY: Index entries always point at live, linked orders.
+            Some(tail) => unsafe { tail.as_ref().next },
+            None => match side {
+                Side::Buy => self.best_bid,
+                Side::Sell => self.best_ask,
+            },
+        };
+        OrderIter {
+            current,
+            _book: PhantomData,
+        }
+    }
+
+    /// Every resting order on both sides: bids best-to-worst, then asks
+    /// best-to-worst. Allocation-free.
+    pub fn iter_all(&self) -> impl Iterator<Item = OrderView> + '_ {
+        self.iter_side(Side::Buy)
+            .chain(self.iter_side(Side::Sell))
+            .map(OrderView::from)
+    }
+
+    /// Typed view of the bid side. See [`SideView`].
+    pub fn bids(&self) -> SideView<'_, view::Buy> {
+        SideView::new(self)
+    }
+
+    /// Typed view of the ask side. See [`SideView`].
+    pub fn asks(&self) -> SideView<'_, view::Sell> {
+        SideView::new(self)
+    }
+
+    /// `(order count, total quantity)` resting on a side. O(N),
+    /// allocation-free; an empty side is `(0, Quantity(0))`. The quantity
+    /// saturates at `u64::MAX`.
+    pub fn side_totals(&self, side: Side) -> (usize, Quantity) {
+        self.iter_side(side)
+            .fold((0, Quantity(0)), |(count, total), order| {
+                (count + 1, Quantity(total.0.saturating_add(order.qty.0)))
+            })
+    }
+
+    /// Total quantity resting on a side. O(N), allocation-free. Saturates
+    /// at `u64::MAX`.
+    pub fn total_volume(&self, side: Side) -> Quantity {
+        Quantity(
+            self.iter_side(side)
+                .fold(0, |total, order| total.saturating_add(order.qty.0)),
+        )
+    }
+
+    /// Total quantity resting at exactly `price` on `side`; `Quantity(0)` if
+    /// the level is empty.
+    ///
+    /// Looks the level up in the price index and sums it back-to-front from
+    /// its tail, so only orders at that price are visited. Allocation-free.
+    /// Saturates at `u64::MAX`.
+    pub fn volume_at_price(&self, side: Side, price: u64) -> Quantity {
+        let price = Price(price);
+        let mut total = 0;
+        let mut current_ptr = self.levels.tail(side, price);
+
+        while let Some(ptr) = current_ptr {
+            let order = unsafe { ptr.as_ref() };
+            if order.price != price {
+                break;
+            }
+            total = order.qty.0.saturating_add(total);
+            current_ptr = order.prev;
+        }
+
+        Quantity(total)
+    }
+
+    /// Value resting at exactly `price` on `side`: the level's visible
+    /// quantity times its price, `0` if the level is empty.
+    ///
+    /// **Scale:** the raw product of the fixed-point `Price` (5 decimals) and
+    /// `Quantity` (3 decimals), i.e. value × 10^8. Computed in `u128`, so it
+    /// cannot overflow. Allocation-free.
+    pub fn notional_at_price(&self, side: Side, price: u64) -> u128 {
+        self.volume_at_price(side, price).0 as u128 * price as u128
+    }
+
+    /// Sum of `price * qty` over every resting order on `side` (visible
+    /// quantity, as in [`depth`](Self::depth)); `0` for an empty side.
+    ///
+    /// Same scale as [`notional_at_price`](Self::notional_at_price). O(N)
+    /// over the side, allocation-free.
+    pub fn total_notional(&self, side: Side) -> u128 {
+        self.iter_side(side)
+            .map(|order| order.price.0 as u128 * order.qty.0 as u128)
+            .sum()
+    }
+
+    /// How many orders are queued ahead of `id` at its price (`0` = front of
+    /// the level), or `None` if `id` is not resting.
+    ///
+    /// Walks back along `prev` from the order, so it costs O(orders ahead).
+    pub fn queue_position(&self, id: u64) -> Option<usize> {
+        let order = unsafe { self.order_index.get(&OrderId(id))?.as_ref() };
+        let mut ahead = 0;
+        let mut current_ptr = order.prev;
+
+        while let Some(ptr) = current_ptr {
+            let prev = unsafe { ptr.as_ref() };
+            if prev.price != order.price {
+                break;
+            }
+            ahead += 1;
+            current_ptr = prev.prev;
+        }
+
+        Some(ahead)
+    }
+
+    /// The `n`th distinct price level on a side (`0` = best), with its total size.
+    ///
+    /// Walks only as far as the requested level and allocates nothing.
+    /// Returns `None` if the side has fewer than `n + 1` levels. The size
+    /// saturates at `u64::MAX`.
+    pub fn nth_level(&self, side: Side, n: usize) -> Option<(Price, Quantity)> {
+        let mut current_ptr = match side {
+            Side::Buy => self.best_bid,
+            Side::Sell => self.best_ask,
+        };
+        let mut level: Option<(Price, Quantity)> = None;
+        let mut index = 0;
+
+        while let Some(ptr) = current_ptr {
+            let order = unsafe { ptr.as_ref() };
+            match level.as_mut() {
+                Some((price, qty)) if *price == order.price => {
+                    qty.0 = qty.0.saturating_add(order.qty.0);
+                }
+                Some(_) if index == n => break,
+                Some(_) => {
+                    index += 1;
+                    level = Some((order.price, order.qty));
+                }
+                None => level = Some((order.price, order.qty)),
+            }
+            current_ptr = order.next;
+        }
+
+        level.filter(|_| index == n)
+    }
+
+    /// Best bid and ask price levels, each with the total quantity resting there.
+    pub fn quote(&self) -> Quote {
+        Quote {
+            bid: self.top_level(Side::Buy),
+            ask: self.top_level(Side::Sell),
+        }
+    }
+
+    /// Packs the top of book into a `Copy` POD for a seqlock publish.
+    ///
+    /// See [`TopOfBookCell`](crate::engine::publish::TopOfBookCell) for the
+    /// writer/reader protocol. Empty sides are encoded as `0`.
+    pub fn pack_top(&self) -> TopOfBook {
+        let (bid_px, bid_sz) = self.top_level(Side::Buy).unwrap_or_default();
+        let (ask_px, ask_sz) = self.top_level(Side::Sell).unwrap_or_default();
+        TopOfBook {
+            bid_px: bid_px.0,
+            bid_sz: bid_sz.0,
+            ask_px: ask_px.0,
+            ask_sz: ask_sz.0,
+            seq: self.version,
+        }
+    }
+
+    /// Returns `true` if the best bid/ask price or size differs from the last call.
+    ///
+    /// This is opt-in: nothing is tracked on the mutating paths. The book only
+    /// caches the [`Quote`] seen by the previous call and re-derives the top when
+    /// the [`version`](Self::version) has moved. Orders changing deeper in the
+    /// book bump the version but do not count as a top-of-book change.
+    pub fn top_changed(&mut self) -> bool {
+        let (seen_version, seen_quote) = self.last_quote;
+        if seen_version == self.version {
+            return false;
+        }
+
+        let quote = self.quote();
+        self.last_quote = (self.version, quote);
+        quote != seen_quote
+    }
+
+    /// How many times the best bid and best ask order have changed, as `(bid, ask)`.
+    ///
+    /// Counted every time the head of a side is replaced (new better price,
+    /// best order cancelled or filled), since creation or the last
+    /// [`reset_stats`](Self::reset_stats). Counts that stop advancing while
+    /// orders keep flowing are a cheap liveness signal for a stale or frozen book.
+    pub fn top_change_counts(&self) -> (u64, u64) {
+        self.top_changes
+    }
+
+    /// Resets the book's monitoring counters. Book state is not affected.
+    pub fn reset_stats(&mut self) {
+        self.top_changes = (0, 0);
+    }
+
+    /// Aggregates the first price level of a side without allocating. The
+    /// size saturates at `u64::MAX`.
+    pub(crate) fn top_level(&self, side: Side) -> Option<(Price, Quantity)> {
+        let head = match side {
+            Side::Buy => self.best_bid,
+            Side::Sell => self.best_ask,
+        }?;
+
+        let price = unsafe { head.as_ref().price };
+        let mut total = Quantity(0);
+        let mut current_ptr = Some(head);
+
+        while let Some(ptr) = current_ptr {
+            let order = unsafe { ptr.as_ref() };
+            if order.price != price {
+                break;
+            }
+            total.0 = total.0.saturating_add(order.qty.0);
+            current_ptr = order.next;
+        }
+
+        Some((price, total))
+    }
+
+    /// The sequence number the next accepted order or trade will receive.
+    ///
+    /// Every accepted placement is stamped with a number at intake (before it
+    /// matches), and every trade it generates takes the following numbers, so
+    /// the sequence is a total order over all events in the book.
+    ///
+    /// A standalone book owns this counter. A book owned by an
+    /// [`OrderBookManager`](crate::engine::manager::OrderBookManager) is lent the
+    /// manager's global counter for each call instead, so sequence numbers are
+    /// unique and monotonic across every symbol the manager runs.
+    pub fn next_seq(&self) -> u64 {
+        self.seq_counter
+    }
+
+    /// Consumes and returns the next sequence number.
+    #[inline]
+    pub(crate) fn take_seq(&mut self) -> u64 {
+        let seq = self.seq_counter;
+        self.seq_counter += 1;
+        seq
+    }
+
+    /// Returns a counter that increases every time the book is mutated.
+    ///
+    /// Consumers can compare two readings to cheaply detect whether anything
+    /// changed in between (see [`DepthSubscriber`](crate::engine::depth::DepthSubscriber)).
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Total order storage across every arena chunk.
+    pub fn capacity_bytes(&self) -> usize {
+        self.order_arenas.iter().map(Arena::capacity).sum()
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.order_arenas.iter().map(Arena::used_bytes).sum()
+    }
+
+    pub fn active_orders(&self) -> usize {
+        self.order_index.len()
+    }
+
+    pub fn free_slots(&self) -> usize {
+        self.free_list.len()
+    }
+}
+
+/// Iterator over the resting orders of one side, best price first.
+///
+/// Created by [`OrderBook::iter_side`].
+pub struct OrderIter<'a> {
+    current: Option<OrderPtr>,
+    _book: PhantomData<&'a OrderBook>,
+}
+
+impl<'a> Iterator for OrderIter<'a> {
+    type Item = &'a Order;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: The iterator borrows the book immutably for 'a, so no node
+        // can be unlinked or recycled while references are handed out.
+        let order = unsafe { self.current?.as_ref() };
+        self.current = order.next;
+        Some(order)
+    }
+}
+
+// Once `current` is `None` it is never reassigned.
+impl core::iter::FusedIterator for OrderIter<'_> {}
+
+/// Price levels shown per side by `OrderBook`'s `Display`.
+#[cfg(feature = "std")]
+const DISPLAY_LEVELS: usize = 10;
+
+/// Debug rendering of the top [`DISPLAY_LEVELS`] levels as a price ladder:
+/// asks from worst to best, a spread line, then bids from best to worst, so
+/// prices descend down the page. Levels are aggregated as in
+/// [`OrderBook::depth`]. Allocates; not for the hot path. Needs the `std`
+/// feature.
+#[cfg(feature = "std")]
+impl fmt::Display for OrderBook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Depth { bids, asks } = self.depth(DISPLAY_LEVELS);
+        if bids.is_empty() && asks.is_empty() {
+            return writeln!(f, "{}: empty book", self.symbol);
+        }
+
+        // The fixed-point impls ignore width flags, so pad their output.
+        let cells = |ladder: &Ladder| -> Vec<(String, String)> {
+            ladder
+                .iter()
+                .map(|(price, qty)| (price.to_string(), qty.to_string()))
+                .collect()
+        };
+        let (bid_cells, ask_cells) = (cells(&bids), cells(&asks));
+        let price_w = bid_cells
+            .iter()
+            .chain(&ask_cells)
+            .map(|c| c.0.len())
+            .max()
+            .unwrap_or(0);
+        let qty_w = bid_cells
+            .iter()
+            .chain(&ask_cells)
+            .map(|c| c.1.len())
+            .max()
+            .unwrap_or(0);
+
+        writeln!(f, "{}", self.symbol)?;
+        for (price, qty) in ask_cells.iter().rev() {
+            writeln!(f, "  ASK {price:>price_w$} {qty:>qty_w$}")?;
+        }
+        match self.spread() {
+            Some(spread) => writeln!(f, "  --- spread {spread} ---")?,
+            None => writeln!(f, "  ---")?,
+        }
+        for (price, qty) in &bid_cells {
+            writeln!(f, "  BID {price:>price_w$} {qty:>qty_w$}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Deep copy for what-if analysis: fork the book, trade against the copy,
+/// drop it.
+///
+/// The clone gets its own arena with the same total capacity, and every
+/// resting order is re-inserted in priority order (keeping its `seq`), so the
+/// list and index are rebuilt rather than aliased. The two books never share
+/// a slot: mutating one cannot affect the other. Settings, counters, parked
+/// stops and accumulated trades are copied; the trade observer and clock are
+/// **not** (a boxed closure cannot be cloned), so the clone starts without
+/// them.
+/// `OrderPtr`s from the original are not valid on the clone.
+///
+/// Allocates; not for the hot path.
+impl Clone for OrderBook {
+    fn clone(&self) -> Self {
+        let capacity = self.capacity_bytes() / mem::size_of::<Order>();
+        let mut book = Self::new(self.symbol, capacity);
+
+        for order in self.iter_side(Side::Buy).chain(self.iter_side(Side::Sell)) {
+            let order_ptr = book
+                .try_alloc_slot(order.clone())
+                .expect("clone has the source book's capacity");
+            unsafe {
+                book.insert_sorted(order_ptr);
+            }
+            book.order_index.insert(order.id, order_ptr);
+        }
+
+        book.version = self.version;
+        book.seq_counter = self.seq_counter;
+        book.modify_missing_policy = self.modify_missing_policy;
+        book.allow_modify_increase = self.allow_modify_increase;
+        book.keep_priority_on_increase = self.keep_priority_on_increase;
+        book.tick_size = self.tick_size;
+        book.quantity_limits = self.quantity_limits;
+        book.kill_switch = self.kill_switch;
+        book.rate_limiter = self.rate_limiter.clone();
+        book.trade_accumulation = self.trade_accumulation;
+        book.trade_accumulator = self.trade_accumulator.clone();
+        book.self_trade_prevention = self.self_trade_prevention;
+        book.match_mode = self.match_mode;
+        book.fee_schedule = self.fee_schedule;
+        book.pricing_policy = self.pricing_policy;
+        book.auto_cancelled.extend_from_slice(&self.auto_cancelled);
+        book.stop_orders = self.stop_orders.clone();
+        book.pegs = self.pegs.clone();
+        book.last_trade_price = self.last_trade_price;
+        book.last_trade_qty = self.last_trade_qty;
+        book.cumulative_volume = self.cumulative_volume;
+        book.top_changes = self.top_changes;
+        book.last_quote = self.last_quote;
+        book.metrics = self.metrics;
+        book.peak_slots = self.peak_slots;
+        book.slot_allocations = self.slot_allocations;
+        book
+    }
+}
+
+/// Encoded size of one order in a snapshot (see `OrderBook::serialize`).
+const SNAPSHOT_ORDER_LEN: usize = 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 4;
+
+/// First line of `OrderBook::to_csv` output.
+#[cfg(feature = "std")]
+const CSV_HEADER: &str = "id,side,price,qty,seq";
+
+/// Parses one `id,side,price,qty,seq` row, or `None` if it is malformed.
+#[cfg(feature = "std")]
+fn parse_csv_row(line: &str) -> Option<Order> {
+    let mut fields = line.split(',').map(str::trim);
+    let id = OrderId(fields.next()?.parse().ok()?);
+    let side = match fields.next()? {
+        "buy" => Side::Buy,
+        "sell" => Side::Sell,
+        _ => return None,
+    };
+    let price: Price = fields.next()?.parse().ok()?;
+    let qty: Quantity = fields.next()?.parse().ok()?;
+    let seq = fields.next()?.parse().ok()?;
+    if fields.next().is_some() || price.0 == 0 || qty.0 == 0 {
+        return None;
+    }
+    Some(Order {
+        seq,
+        ..Order::new(id, side, price, qty)
+    })
+}
+
+/// Cursor over snapshot bytes; every read fails cleanly on truncation.
+struct SnapshotReader<'a>(&'a [u8]);
+
+impl<'a> SnapshotReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], OrderBookError> {
+        if self.0.len() < len {
+            return Err(OrderBookError::InvalidSnapshot);
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], OrderBookError> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.bytes(N)?);
+        Ok(out)
+    }
+}
+
+/// The node a request's unfilled remainder rests as, stamped with its
+/// intake `seq` and placement time. An iceberg shows its first slice and
+/// hides the rest.
+fn resting_order(req: &OrderRequest, seq: u64, placed_at: u64, remaining_qty: Quantity) -> Order {
+    let order = Order {
+        display_qty: req.display_qty,
+        seq,
+        placed_at,
+        expiry: req.expiry,
+        rest_mode: req.rest_mode,
+        account: req.account,
+        ..Order::new(req.id, req.side, req.price, remaining_qty)
+    };
+    Order {
+        qty: order.slice_of(remaining_qty),
+        ..order
+    }
+}
+
+/// How to reverse one applied step of `OrderBook::transaction`.
+enum Undo {
+    /// Remove an order the transaction placed.
+    Placed(OrderId),
+    /// Put a resting order back as it was (cancelled or modified).
+    Resting(Order),
+    /// Re-park a cancelled stop at its old position.
+    Stop(usize, StopOrder),
+}
+
+/// A resting pegged order's offset from the opposite best (see
+/// `OrderBook::place_pegged`).
+#[derive(Debug, Clone, Copy)]
+struct Peg {
+    id: OrderId,
+    offset: i64,
+}
+
+/// The pegged orders on one side, oldest first, and the opposite best they
+/// were all last priced against (`None` if they may disagree). May hold
+/// stale entries for pegs that have since left the book, dropped on the
+/// group's next reprice.
+#[derive(Debug, Clone, Default)]
+struct PegGroup {
+    pegs: Vec<Peg>,
+    reference: Option<Price>,
+}
+
+/// A stop-limit order waiting for its trigger (see `OrderBook::place_stop_limit`).
+#[derive(Debug, Clone, Copy)]
+struct StopOrder {
+    id: OrderId,
+    side: Side,
+    trigger: Price,
+    limit: Price,
+    qty: Quantity,
+}
+
+impl StopOrder {
+    /// Buy stops fire as the market trades up through the trigger, sell stops
+    /// as it trades down through it.
+    fn triggered_by(&self, last: Price) -> bool {
+        match self.side {
+            Side::Buy => last >= self.trigger,
+            Side::Sell => last <= self.trigger,
+        }
     }
 }
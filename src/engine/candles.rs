@@ -0,0 +1,111 @@
+use crate::engine::matcher::Trade;
+use crate::storage::layout::{Price, Quantity};
+
+/// One OHLC bar: the trades of a single time bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candle {
+    /// Start of the bucket, in the caller's clock (a multiple of the
+    /// aggregator's `interval`).
+    pub start: u64,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    /// Quantity traded in the bucket. Saturates at `u64::MAX`.
+    pub volume: Quantity,
+}
+
+impl Candle {
+    fn new(start: u64, trade: &Trade) -> Self {
+        Self {
+            start,
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: trade.quantity,
+        }
+    }
+
+    fn push(&mut self, trade: &Trade) {
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.close = trade.price;
+        self.volume.0 = self.volume.0.saturating_add(trade.quantity.0);
+    }
+}
+
+/// Builds OHLC [`Candle`]s from a trade stream, one per `interval`-wide
+/// time bucket.
+///
+/// Consumes [`Trade`]s only (e.g. from `OrderBook::on_trade` or the `Vec`
+/// a placement returns), never book state. `Trade` carries no wall-clock
+/// time, so time is supplied by the caller through [`roll`](Self::roll),
+/// exactly like [`DepthSubscriber`](crate::engine::depth::DepthSubscriber):
+/// the aggregator never reads a clock and stays deterministic under replay.
+///
+/// **Usage:** call `roll(now)` before pushing each trade (and on a timer, so
+/// a quiet market still closes its bar). Buckets are aligned to multiples of
+/// `interval`: a bar is emitted by the first `roll` in a later bucket. A
+/// bucket with no trades emits nothing. Allocation-free.
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    interval: u64,
+    /// Bucket the open bar belongs to, set by the first `roll`.
+    bucket: Option<u64>,
+    current: Option<Candle>,
+}
+
+impl CandleAggregator {
+    /// Creates an aggregator emitting one bar per `interval` time units.
+    ///
+    /// # Panics
+    /// If `interval` is `0`.
+    pub fn new(interval: u64) -> Self {
+        assert!(interval > 0, "candle interval must be non-zero");
+        Self {
+            interval,
+            bucket: None,
+            current: None,
+        }
+    }
+
+    /// Adds a trade to the open bar.
+    pub fn push(&mut self, trade: &Trade) {
+        match self.current.as_mut() {
+            Some(candle) => candle.push(trade),
+            None => {
+                let start = self.bucket.unwrap_or(0) * self.interval;
+                self.current = Some(Candle::new(start, trade));
+            }
+        }
+    }
+
+    /// Advances time to `now`. If that starts a new bucket, the open bar is
+    /// closed and returned (`None` if it saw no trades).
+    ///
+    /// Trades pushed before the first `roll` join the bucket it starts.
+    /// A `now` earlier than the open bucket is ignored.
+    pub fn roll(&mut self, now: u64) -> Option<Candle> {
+        let bucket = now / self.interval;
+        match self.bucket {
+            Some(open) if bucket <= open => None,
+            Some(_) => {
+                self.bucket = Some(bucket);
+                self.current.take()
+            }
+            None => {
+                self.bucket = Some(bucket);
+                if let Some(candle) = self.current.as_mut() {
+                    candle.start = bucket * self.interval;
+                }
+                None
+            }
+        }
+    }
+
+    /// The bar still being built, if it has seen a trade.
+    pub fn current(&self) -> Option<&Candle> {
+        self.current.as_ref()
+    }
+}
@@ -0,0 +1,141 @@
+use crate::engine::book::OrderBook;
+use crate::storage::layout::{Price, Quantity};
+use alloc::vec::Vec;
+
+/// One side's aggregated `(Price, Quantity)` levels, best price first.
+pub type Ladder = Vec<(Price, Quantity)>;
+
+/// An aggregated (L2) snapshot of the top of the book.
+///
+/// Each entry is a distinct price level with the summed quantity of every
+/// order resting at that price. Both ladders are ordered best price first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Depth {
+    pub bids: Ladder,
+    pub asks: Ladder,
+}
+
+/// The best price level on each side with its aggregated size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Quote {
+    pub bid: Option<(Price, Quantity)>,
+    pub ask: Option<(Price, Quantity)>,
+}
+
+/// A point-in-time aggregated (L2) copy of the whole book, taken by
+/// [`OrderBook::snapshot`].
+///
+/// Owns its ladders outright, with no pointers into the book's arena, so it
+/// is `Send + Sync`: the writer can hand it (e.g. in an `Arc`) to readers on
+/// other threads and keep mutating the book. It is a copy, not a live view,
+/// and never changes after it is taken.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookSnapshot {
+    version: u64,
+    bids: Ladder,
+    asks: Ladder,
+}
+
+impl BookSnapshot {
+    pub(crate) fn new(version: u64, bids: Ladder, asks: Ladder) -> Self {
+        Self {
+            version,
+            bids,
+            asks,
+        }
+    }
+
+    /// The book [`version`](OrderBook::version) the snapshot was taken at.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn best_bid_price(&self) -> Option<Price> {
+        self.bids.first().map(|&(price, _)| price)
+    }
+
+    pub fn best_ask_price(&self) -> Option<Price> {
+        self.asks.first().map(|&(price, _)| price)
+    }
+
+    /// The top `levels` price points on each side, as [`OrderBook::depth`]
+    /// returned them when the snapshot was taken.
+    pub fn depth(&self, levels: usize) -> Depth {
+        Depth {
+            bids: self.bids.iter().take(levels).copied().collect(),
+            asks: self.asks.iter().take(levels).copied().collect(),
+        }
+    }
+
+    /// Every bid level, best price first.
+    pub fn bids(&self) -> &[(Price, Quantity)] {
+        &self.bids
+    }
+
+    /// Every ask level, best price first.
+    pub fn asks(&self) -> &[(Price, Quantity)] {
+        &self.asks
+    }
+}
+
+/// A conflating depth feed for slow consumers (GUIs, remote clients).
+///
+/// Instead of forwarding every delta, the subscriber remembers the book
+/// [`version`](OrderBook::version) it last published and emits a fresh
+/// top-N [`Depth`] from [`poll`](Self::poll) only when:
+/// 1. The book has been mutated since the last emitted snapshot (dirty), and
+/// 2. At least `interval` time units have elapsed since that snapshot.
+///
+/// **Conflation:** Intermediate book states between two polls are
+/// intentionally dropped. A consumer only ever sees the latest state, never
+/// the path taken to reach it. Use the `Vec<Trade>` returned by the book if
+/// every event matters.
+///
+/// Time is supplied by the caller (same units as `interval`), so the
+/// subscriber never reads a clock and stays deterministic under replay.
+#[derive(Debug, Clone)]
+pub struct DepthSubscriber {
+    levels: usize,
+    interval: u64,
+    last_version: Option<u64>,
+    last_emit: Option<u64>,
+}
+
+impl DepthSubscriber {
+    /// Creates a subscriber publishing the top `levels` price points per side,
+    /// at most once every `interval` time units.
+    pub fn new(levels: usize, interval: u64) -> Self {
+        Self {
+            levels,
+            interval,
+            last_version: None,
+            last_emit: None,
+        }
+    }
+
+    /// Returns `true` if the book changed since the last emitted snapshot.
+    pub fn is_dirty(&self, book: &OrderBook) -> bool {
+        self.last_version != Some(book.version())
+    }
+
+    /// Emits the current depth if the book is dirty and the throttle interval
+    /// has elapsed, otherwise returns `None`.
+    ///
+    /// A throttled poll does not clear the dirty state, so the pending change
+    /// is published by the first poll after the interval expires.
+    pub fn poll(&mut self, book: &OrderBook, now: u64) -> Option<Depth> {
+        if !self.is_dirty(book) {
+            return None;
+        }
+
+        if let Some(last) = self.last_emit
+            && now.saturating_sub(last) < self.interval
+        {
+            return None;
+        }
+
+        self.last_version = Some(book.version());
+        self.last_emit = Some(now);
+        Some(book.depth(self.levels))
+    }
+}
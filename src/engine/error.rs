@@ -0,0 +1,123 @@
+use crate::storage::layout::OrderId;
+use core::fmt;
+
+/// Reasons an order-entry request can be rejected by the [`OrderBook`].
+///
+/// Every variant is `Copy` and carries no heap data, so rejecting an order
+/// never allocates (unlike the `String` errors this replaces).
+///
+/// [`OrderBook`]: crate::engine::book::OrderBook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBookError {
+    /// An order with this ID is already resting in the book.
+    DuplicateId(OrderId),
+    /// No resting order has this ID.
+    OrderNotFound(OrderId),
+    /// The order would rest but every arena slot is in use.
+    ArenaFull,
+    /// The price is not a valid limit price (zero).
+    InvalidPrice,
+    /// The order quantity is zero.
+    InvalidQuantity,
+    /// The caller's trade buffer has no room for the trades this order generates.
+    TradeBufferFull,
+    /// The account's order-entry rate limit is exhausted.
+    RateLimited,
+    /// No book is registered for the requested symbol.
+    UnknownSymbol,
+    /// The amendment would re-queue the order and the caller asked to keep
+    /// its priority instead.
+    WouldLosePriority(OrderId),
+    /// An amendment asked for more than the order's resting quantity where
+    /// increases are not allowed.
+    WouldIncreaseQty(OrderId),
+    /// The operator kill switch is on: only cancels are accepted.
+    KillSwitchActive,
+    /// The request combines flags that cannot both hold (e.g. post-only IOC).
+    InvalidOrderType,
+    /// A post-only order would have matched on arrival.
+    PostOnlyWouldCross(OrderId),
+    /// Snapshot bytes are truncated, malformed or for a different symbol.
+    InvalidSnapshot,
+    /// A CSV dump has a missing header or a malformed row at this 1-based
+    /// line number.
+    InvalidCsvRow(usize),
+}
+
+impl OrderBookError {
+    /// FIX `OrdRejReason` (tag 103) for this rejection.
+    ///
+    /// The human-readable `Text` (tag 58) is the `Display` output.
+    ///
+    /// | Variant              | Code | FIX meaning                      |
+    /// | :------------------- | ---: | :------------------------------- |
+    /// | `UnknownSymbol`      |    1 | Unknown symbol                   |
+    /// | `DuplicateId`        |    6 | Duplicate order                  |
+    /// | `OrderNotFound`      |    5 | Unknown order                    |
+    /// | `RateLimited`        |    3 | Order exceeds limit              |
+    /// | `InvalidQuantity`    |   13 | Incorrect quantity               |
+    /// | `ArenaFull`          |   99 | Other                            |
+    /// | `TradeBufferFull`    |   99 | Other                            |
+    /// | `WouldLosePriority`  |   99 | Other                            |
+    /// | `WouldIncreaseQty`   |   13 | Incorrect quantity               |
+    /// | `KillSwitchActive`   |    2 | Exchange closed                  |
+    /// | `InvalidOrderType`   |   11 | Unsupported order characteristic |
+    /// | `PostOnlyWouldCross` |   99 | Other                            |
+    /// | `InvalidPrice`       |   99 | Other                            |
+    /// | `InvalidSnapshot`    |   99 | Other                            |
+    /// | `InvalidCsvRow`      |   99 | Other                            |
+    ///
+    /// These values are part of the wire contract: do not renumber them.
+    pub fn fix_reject_code(&self) -> u32 {
+        match self {
+            Self::UnknownSymbol => 1,
+            Self::DuplicateId(_) => 6,
+            Self::OrderNotFound(_) => 5,
+            Self::RateLimited => 3,
+            Self::KillSwitchActive => 2,
+            Self::InvalidOrderType => 11,
+            Self::InvalidQuantity | Self::WouldIncreaseQty(_) => 13,
+            Self::ArenaFull
+            | Self::TradeBufferFull
+            | Self::WouldLosePriority(_)
+            | Self::PostOnlyWouldCross(_)
+            | Self::InvalidPrice
+            | Self::InvalidSnapshot
+            | Self::InvalidCsvRow(_) => 99,
+        }
+    }
+}
+
+impl fmt::Display for OrderBookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateId(id) => write!(f, "Duplicate Order ID: {}", id.0),
+            Self::OrderNotFound(id) => write!(f, "Order ID {} not found in book.", id.0),
+            Self::ArenaFull => write!(f, "Order book is at capacity"),
+            Self::InvalidPrice => write!(f, "Invalid price"),
+            Self::InvalidQuantity => write!(f, "Invalid quantity"),
+            Self::InvalidSnapshot => write!(f, "Invalid order book snapshot"),
+            Self::InvalidCsvRow(line) => write!(f, "Malformed order book CSV at line {line}"),
+            Self::TradeBufferFull => write!(f, "Trade buffer is full"),
+            Self::RateLimited => write!(f, "Order rate limit exceeded"),
+            Self::UnknownSymbol => write!(f, "Unknown symbol"),
+            Self::KillSwitchActive => write!(f, "Kill switch active: only cancels accepted"),
+            Self::InvalidOrderType => write!(f, "Contradictory order type flags"),
+            Self::PostOnlyWouldCross(id) => {
+                write!(f, "Post-only order ID {} would cross the spread", id.0)
+            }
+            Self::WouldLosePriority(id) => {
+                write!(f, "Modify of order ID {} would lose queue priority", id.0)
+            }
+            Self::WouldIncreaseQty(id) => {
+                write!(
+                    f,
+                    "Modify of order ID {} would increase its resting quantity",
+                    id.0
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for OrderBookError {}
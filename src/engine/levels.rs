@@ -0,0 +1,102 @@
+use crate::storage::layout::{OrderPtr, Price, Side};
+use alloc::collections::BTreeMap;
+use core::ops::Bound::{Excluded, Unbounded};
+
+/// A price-level index over the intrusive order list.
+///
+/// Maps every occupied price on a side to the **tail** (newest order) of that
+/// level's FIFO. The linked list itself is unchanged, so every traversal
+/// (matching, depth, etc.) still follows `next` pointers and matching still
+/// consumes each level front-to-back. The index makes `insert_sorted` an
+/// O(log L) lookup plus an O(1) tail link, where L is the number of distinct
+/// price levels, regardless of how many orders rest ahead.
+///
+/// **Allocation Note:**
+/// `BTreeMap` allocates a node when a *new* price level appears. Appending to
+/// an existing level is allocation-free.
+#[derive(Default)]
+pub(crate) struct LevelIndex {
+    bids: BTreeMap<Price, OrderPtr>,
+    asks: BTreeMap<Price, OrderPtr>,
+}
+
+impl LevelIndex {
+    fn side(&self, side: Side) -> &BTreeMap<Price, OrderPtr> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<Price, OrderPtr> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Returns the order a new order at `price` must be linked *after*.
+    ///
+    /// - Level exists: its tail (time priority within the level).
+    /// - Otherwise: the tail of the next-better level.
+    /// - `None`: the new order becomes the head of the side (new best price).
+    pub(crate) fn predecessor(&self, side: Side, price: Price) -> Option<OrderPtr> {
+        self.tail(side, price)
+            .or_else(|| self.better_tail(side, price))
+    }
+
+    /// The tail of the closest level strictly better than `price`: the order
+    /// just ahead of every order at `price` or worse. `None` if no resting
+    /// price is better.
+    pub(crate) fn better_tail(&self, side: Side, price: Price) -> Option<OrderPtr> {
+        let levels = self.side(side);
+        // "Better" is higher for bids and lower for asks.
+        match side {
+            Side::Buy => levels.range((Excluded(price), Unbounded)).next(),
+            Side::Sell => levels.range(..price).next_back(),
+        }
+        .map(|(_, tail)| *tail)
+    }
+
+    /// The newest order at exactly `price`, if the level exists.
+    pub(crate) fn tail(&self, side: Side, price: Price) -> Option<OrderPtr> {
+        self.side(side).get(&price).copied()
+    }
+
+    /// Number of occupied price levels on `side`.
+    pub(crate) fn level_count(&self, side: Side) -> usize {
+        self.side(side).len()
+    }
+
+    /// Records `ptr` as the new tail of its price level.
+    pub(crate) fn push_tail(&mut self, side: Side, price: Price, ptr: OrderPtr) {
+        self.side_mut(side).insert(price, ptr);
+    }
+
+    /// Updates the index for an order that is about to be unlinked.
+    ///
+    /// `prev` is the order's current predecessor in the list. If the removed
+    /// order was the tail of its level, the tail moves back to `prev` when it
+    /// shares the price, otherwise the level is now empty and dropped.
+    pub(crate) fn unlink(
+        &mut self,
+        side: Side,
+        price: Price,
+        ptr: OrderPtr,
+        prev: Option<OrderPtr>,
+    ) {
+        let levels = self.side_mut(side);
+        if levels.get(&price) != Some(&ptr) {
+            return;
+        }
+
+        match prev {
+            Some(p) if unsafe { p.as_ref().price } == price => {
+                levels.insert(price, p);
+            }
+            _ => {
+                levels.remove(&price);
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::engine::book::OrderBook;
+use crate::engine::error::OrderBookError;
+use crate::engine::matcher::Trade;
+use crate::engine::request::OrderRequest;
+use crate::storage::layout::{OrderId, OrderPtr, Quantity, Side};
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use rustc_hash::FxBuildHasher;
+
+/// Runs many single-symbol [`OrderBook`]s from one engine thread.
+///
+/// **Global Sequencing:**
+/// The manager owns one monotonic sequence counter for the whole venue. For
+/// the duration of every call it lends that counter to the target book (the
+/// book's own [`next_seq`](OrderBook::next_seq) is overwritten on the way in
+/// and read back on the way out). Every order and trade is therefore stamped
+/// from a single sequence regardless of symbol, which gives a deterministic,
+/// auditable cross-symbol event order for consolidated audit trails and
+/// venue-wide replay.
+///
+/// Within one book, per-book ordering is unchanged: the global sequence is
+/// still strictly increasing there, it just has gaps where other symbols
+/// consumed numbers.
+///
+/// The counter is a plain `u64`: the manager is single-threaded by design
+/// (`&mut self` on every entry point), so no atomics are needed.
+#[derive(Default)]
+pub struct OrderBookManager {
+    books: HashMap<&'static str, OrderBook, FxBuildHasher>,
+    next_seq: u64,
+}
+
+impl OrderBookManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new book. Replaces (and drops) any book with the same symbol.
+    pub fn add_book(&mut self, symbol: &'static str, capacity: usize) {
+        self.books.insert(symbol, OrderBook::new(symbol, capacity));
+    }
+
+    pub fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol)
+    }
+
+    /// The global sequence number the next event on any book will receive.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    pub fn place(
+        &mut self,
+        symbol: &str,
+        req: OrderRequest,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.with_book(symbol, |book| book.place(req))
+    }
+
+    /// Routes a plain GTC limit order to `symbol`'s book.
+    ///
+    /// Ids only need to be unique within a symbol: the same id may rest on
+    /// two books at once.
+    pub fn place_limit_order(
+        &mut self,
+        symbol: &str,
+        id: u64,
+        side: Side,
+        price: u64,
+        qty: u64,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.with_book(symbol, |book| book.place_limit_order(id, side, price, qty))
+    }
+
+    pub fn modify(
+        &mut self,
+        symbol: &str,
+        id: u64,
+        new_price: u64,
+        new_qty: u64,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.with_book(symbol, |book| book.modify_order(id, new_price, new_qty))
+    }
+
+    pub fn cancel(&mut self, symbol: &str, id: u64) -> Result<(OrderId, Quantity), OrderBookError> {
+        self.with_book(symbol, |book| book.cancel_order(id))
+    }
+
+    /// Runs `f` on the book for `symbol` with the global sequence counter lent to it.
+    fn with_book<T>(
+        &mut self,
+        symbol: &str,
+        f: impl FnOnce(&mut OrderBook) -> Result<T, OrderBookError>,
+    ) -> Result<T, OrderBookError> {
+        let book = self
+            .books
+            .get_mut(symbol)
+            .ok_or(OrderBookError::UnknownSymbol)?;
+
+        book.seq_counter = self.next_seq;
+        let result = f(book);
+        self.next_seq = book.seq_counter;
+
+        result
+    }
+}
+
+/// Multi-symbol entry point taking plain `(id, side, price, qty)` orders:
+/// a thin layer over [`OrderBookManager`] that only routes.
+///
+/// Each call looks up the symbol's book and delegates to it, so matching is
+/// exactly that of a lone [`OrderBook`], and sequencing is the manager's
+/// global counter. Ids are unique per symbol, not across symbols. A symbol
+/// that was never added fails with `OrderBookError::UnknownSymbol`.
+#[derive(Default)]
+pub struct MatchingEngine {
+    manager: OrderBookManager,
+}
+
+impl MatchingEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lists `symbol` with a book of `capacity` orders. Replaces (and drops)
+    /// any book already listed under it.
+    pub fn add_symbol(&mut self, symbol: &'static str, capacity: usize) {
+        self.manager.add_book(symbol, capacity);
+    }
+
+    /// Places a GTC limit order on `symbol`'s book.
+    pub fn place(
+        &mut self,
+        symbol: &str,
+        id: u64,
+        side: Side,
+        price: u64,
+        qty: u64,
+    ) -> Result<(Option<OrderPtr>, Vec<Trade>), OrderBookError> {
+        self.manager.place_limit_order(symbol, id, side, price, qty)
+    }
+
+    pub fn cancel(&mut self, symbol: &str, id: u64) -> Result<(OrderId, Quantity), OrderBookError> {
+        self.manager.cancel(symbol, id)
+    }
+
+    pub fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.manager.book(symbol)
+    }
+
+    /// The manager underneath (e.g. for the global [`next_seq`](OrderBookManager::next_seq)).
+    pub fn manager(&self) -> &OrderBookManager {
+        &self.manager
+    }
+
+    /// The manager underneath, for order types beyond plain limits.
+    pub fn manager_mut(&mut self) -> &mut OrderBookManager {
+        &mut self.manager
+    }
+}
@@ -1,5 +1,9 @@
 use crate::engine::book::OrderBook;
-use crate::storage::layout::{OrderId, Price, Quantity, Side};
+use crate::engine::request::OrderRequest;
+use crate::storage::layout::{Order, OrderId, OrderPtr, Price, Quantity, RestMode, Side};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
 
 /// Heuristic for pre-allocating the Trade vector.
 ///
@@ -22,6 +26,312 @@ pub struct Trade {
     pub quantity: Quantity,
     /// The side of the Maker (e.g., if MakerSide = Buy, this was a Sell taking a Buy).
     pub maker_side: Side,
+    /// The Maker's remaining quantity after this fill, hidden iceberg reserve
+    /// included (`Order::total_qty`).
+    /// Zero means the Maker was fully filled and removed from the book.
+    pub maker_leaves_qty: Quantity,
+    /// Sequence number of this execution, drawn from the same counter as
+    /// order intake (see `OrderBook::next_seq`).
+    pub seq: u64,
+    /// The maker's intake sequence number (`Order::seq`), i.e. its time
+    /// priority when it was hit.
+    pub maker_seq: u64,
+    /// The taker's gateway receive time (`OrderRequest::recv_ts`), if given.
+    /// Informational only: copied through for latency attribution.
+    pub taker_recv_ts: Option<u64>,
+    /// Fee charged to the maker, in `Price` units (see [`FeeSchedule`]).
+    pub maker_fee: u64,
+    /// Fee charged to the taker, in `Price` units (see [`FeeSchedule`]).
+    pub taker_fee: u64,
+}
+
+/// Maker and taker fee rates in basis points, set per book with
+/// `OrderBook::set_fee_schedule`.
+///
+/// The default charges nothing, so every `Trade` fee is `0`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    pub maker_bps: u32,
+    pub taker_bps: u32,
+}
+
+impl FeeSchedule {
+    /// `(maker_fee, taker_fee)` for a fill of `qty` at `price`.
+    ///
+    /// **Units:** fees are in the fixed-point scale of `Price` (5 decimals),
+    /// i.e. `price * quantity * bps / 10_000` with the quantity's 3 decimal
+    /// places divided out. Computed in `u128` with checked multiplication;
+    /// a fee too large for a `u64` **saturates** at `u64::MAX` rather than
+    /// wrapping to a small charge.
+    ///
+    /// **Rounding:** always **up** to the next price tick. A non-zero rate on
+    /// a non-zero fill never rounds to a free trade.
+    pub fn fees(&self, price: Price, qty: Quantity) -> (u64, u64) {
+        let fee = |bps: u32| {
+            (price.0 as u128)
+                .checked_mul(qty.0 as u128)
+                .and_then(|notional| notional.checked_mul(bps as u128))
+                .and_then(|scaled| u64::try_from(scaled.div_ceil(10_000 * 1_000)).ok())
+                .unwrap_or(u64::MAX)
+        };
+        (fee(self.maker_bps), fee(self.taker_bps))
+    }
+}
+
+/// Callback registered with `OrderBook::on_trade`. `Send`, so the book that
+/// owns it can be moved to another thread.
+pub type TradeObserver = Box<dyn FnMut(&Trade) + Send>;
+
+/// A run of adjacent [`Trade`]s compacted into one tape entry.
+///
+/// Produced by [`Trade::aggregate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggTrade {
+    pub price: Price,
+    /// Sum of `quantity` over the merged trades.
+    pub total_qty: Quantity,
+    /// Number of merged trades (one per maker fill).
+    pub maker_count: u32,
+    pub taker_id: OrderId,
+}
+
+impl Trade {
+    /// The taker's side: the opposite of [`maker_side`](Self::maker_side).
+    #[inline]
+    pub fn aggressor_side(&self) -> Side {
+        self.maker_side.opposite()
+    }
+
+    /// `true` if a buyer took liquidity (an up-tick on a colored tape),
+    /// `false` if a seller did.
+    #[inline]
+    pub fn is_buy_aggressor(&self) -> bool {
+        self.aggressor_side() == Side::Buy
+    }
+
+    /// `price * quantity`, at the same scale as
+    /// `OrderBook::notional_at_price` (value × 10^8). Computed in `u128`, so
+    /// it cannot overflow.
+    #[inline]
+    pub fn notional(&self) -> u128 {
+        self.price.0 as u128 * self.quantity.0 as u128
+    }
+
+    /// Compacts a trade slice by merging **adjacent** trades with the same
+    /// price and taker.
+    ///
+    /// Only consecutive runs are merged: two fills at the same price separated
+    /// by a trade at another price (or from another taker, e.g. in a drained
+    /// accumulator) stay separate entries. The output preserves tape order.
+    ///
+    /// **Allocation Note:**
+    /// Allocates exactly once, sized for the worst case (`trades.len()`, when
+    /// nothing merges). The matcher is not involved.
+    pub fn aggregate(trades: &[Trade]) -> Vec<AggTrade> {
+        let mut aggregated: Vec<AggTrade> = Vec::with_capacity(trades.len());
+
+        for trade in trades {
+            match aggregated.last_mut() {
+                Some(last) if last.price == trade.price && last.taker_id == trade.taker_id => {
+                    last.total_qty.0 += trade.quantity.0;
+                    last.maker_count += 1;
+                }
+                _ => aggregated.push(AggTrade {
+                    price: trade.price,
+                    total_qty: trade.quantity,
+                    maker_count: 1,
+                    taker_id: trade.taker_id,
+                }),
+            }
+        }
+
+        aggregated
+    }
+}
+
+/// Merges **adjacent** trades between the same maker and taker at the same
+/// price into one [`Trade`], summing `quantity` and both fees.
+///
+/// This is what an iceberg that refills against a single taker produces:
+/// one trade per slice, all for the same `(maker_id, taker_id, price)`.
+/// Unlike [`Trade::aggregate`], the result is still a per-maker fill, so it
+/// can feed anything that consumes trades. A merged trade reflects its last
+/// fill: `seq`, `maker_seq` and `maker_leaves_qty` come from the final slice
+/// of the run. Quantity and fees saturate at `u64::MAX`.
+///
+/// Allocates exactly once, sized for the worst case (`trades.len()`).
+pub fn coalesce_trades(trades: &[Trade]) -> Vec<Trade> {
+    let mut coalesced: Vec<Trade> = Vec::with_capacity(trades.len());
+
+    for trade in trades {
+        match coalesced.last_mut() {
+            Some(last)
+                if last.maker_id == trade.maker_id
+                    && last.taker_id == trade.taker_id
+                    && last.price == trade.price =>
+            {
+                last.quantity.0 = last.quantity.0.saturating_add(trade.quantity.0);
+                last.maker_fee = last.maker_fee.saturating_add(trade.maker_fee);
+                last.taker_fee = last.taker_fee.saturating_add(trade.taker_fee);
+                last.maker_leaves_qty = trade.maker_leaves_qty;
+                last.seq = trade.seq;
+                last.maker_seq = trade.maker_seq;
+            }
+            _ => coalesced.push(trade.clone()),
+        }
+    }
+
+    coalesced
+}
+
+/// Where the book delivers the trades generated by a placement.
+///
+/// See `OrderBook::set_trade_accumulation`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TradeAccumulation {
+    /// Trades are returned from each call (default).
+    #[default]
+    Off,
+    /// Trades are appended to a book-owned buffer with no size limit.
+    Unbounded,
+    /// Trades are appended to a book-owned buffer of at most this many trades.
+    Capped(usize),
+}
+
+/// What price a crossing order trades at.
+///
+/// Set per book with `OrderBook::set_pricing_policy`. Only the `price` of
+/// each `Trade` (and the fees, last trade price and dry runs derived from
+/// it) changes: which makers cross, and in what order, does not.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PricingPolicy {
+    /// The resting order's price (default). All price improvement goes to
+    /// the taker.
+    #[default]
+    MakerPrice,
+    /// Halfway between the taker's limit and the maker's price, splitting the
+    /// improvement.
+    ///
+    /// **Rounding:** toward the maker's price, onto the book's tick grid, so
+    /// an odd half-tick goes to the taker and the price never leaves
+    /// `[maker, limit]`. Market orders have no limit and trade at the maker's
+    /// price.
+    Midpoint,
+}
+
+impl PricingPolicy {
+    /// Trade price for a taker on `taker_side` with limit `taker_limit`
+    /// (`None` for a market order) against a maker at `maker_price`.
+    pub fn trade_price(
+        self,
+        taker_side: Side,
+        taker_limit: Option<Price>,
+        maker_price: Price,
+        tick: u64,
+    ) -> Price {
+        let (Self::Midpoint, Some(limit)) = (self, taker_limit) else {
+            return maker_price;
+        };
+        let tick = tick.max(1) as u128;
+        let sum = limit.0 as u128 + maker_price.0 as u128;
+        // A maker resting from before the tick size was set may sit off the
+        // grid, so clamp to it after snapping.
+        let mid = match taker_side {
+            Side::Buy => (sum / 2 / tick * tick).max(maker_price.0 as u128),
+            Side::Sell => (sum.div_ceil(2).div_ceil(tick) * tick).min(maker_price.0 as u128),
+        };
+        Price(mid as u64)
+    }
+}
+
+/// How a taker's quantity is shared among the makers at a price level.
+///
+/// Set per book with `OrderBook::set_match_mode`. Price priority holds in
+/// both modes: a worse level is only reached once the better one is empty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// FIFO: makers at a level fill in time priority (default).
+    #[default]
+    PriceTime,
+    /// Each maker at the level fills `floor(T * maker_qty / level_total)`,
+    /// where `T` is the part of the taker the level can absorb. The rounding
+    /// remainder goes to the largest maker, so the level fills exactly `T`.
+    ///
+    /// Time priority plays no part, except as the tie-break for "largest".
+    /// `CancelIfCrossed` and self-trade makers are cleared from the level
+    /// first, as FIFO matching would on reaching them.
+    /// `TakerConstraints::min_fill_per_maker` does not apply. Iceberg
+    /// makers share by their visible slice; refilled slices share again in
+    /// a new round.
+    ProRata,
+}
+
+/// What happens when a taker reaches a maker from its own account.
+///
+/// Set per book with `OrderBook::set_self_trade_prevention`. Account `0`
+/// means "no account" and is never checked. Neither side of a prevented
+/// self-trade produces a `Trade`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// Pull the resting maker (reported via `OrderBook::drain_auto_cancelled`)
+    /// and keep matching against the next one.
+    CancelResting,
+    /// Stop matching and cancel the taker's remainder; it never rests.
+    CancelIncoming,
+    /// Reduce both orders by the overlapping quantity without trading. A maker
+    /// decremented to zero is removed and reported like `CancelResting`.
+    Decrement,
+}
+
+/// Optional per-taker restrictions applied while walking the book.
+///
+/// The default value imposes no restrictions, which is the plain
+/// price-time matching performed by [`execute_match`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TakerConstraints {
+    /// Minimum quantity the taker accepts from any single maker.
+    ///
+    /// **Semantics:**
+    /// - A maker whose fill would be smaller than this is skipped, and the
+    ///   taker moves on to the next maker **at the same price**.
+    /// - A fill that completes the taker's remaining quantity is always
+    ///   accepted, even if it is below the minimum.
+    /// - Once a maker has been skipped, matching never moves to a worse price
+    ///   level: trading through better-priced liquidity that was rejected only
+    ///   because of its size would violate price priority. Matching stops there.
+    /// - A remainder that still crosses the skipped makers cannot rest without
+    ///   crossing the book, so the book cancels it instead of placing it.
+    pub min_fill_per_maker: Option<Quantity>,
+    /// Furthest the sweep may move from the opposite best price it started
+    /// at, in `Price` units.
+    ///
+    /// **Semantics:**
+    /// - A buy never trades above `best_ask + max_slippage`, a sell never
+    ///   below `best_bid - max_slippage`, with both bests read once, before
+    ///   the first fill. It tightens the taker's own limit, never widens it.
+    /// - Makers past the bound are not touched: matching stops there.
+    /// - A remainder that still crosses those makers cannot rest without
+    ///   crossing the book, so the book cancels it, IOC-style.
+    /// - The trade price is unaffected (see [`PricingPolicy`]).
+    pub max_slippage: Option<Price>,
+    /// The taker's account, checked against makers when the book has
+    /// [`SelfTradePrevention`] enabled. `0` (the default) is never checked.
+    /// `OrderBook::place` fills this in from `OrderRequest::account`.
+    pub account: u32,
+}
+
+impl TakerConstraints {
+    /// The worst price the taker may trade at: `limit`, tightened to
+    /// [`max_slippage`](Self::max_slippage) past the opposite `best` price.
+    fn sweep_limit(&self, taker_side: Side, limit: Price, best: Option<Price>) -> Price {
+        let (Some(slippage), Some(best)) = (self.max_slippage, best) else {
+            return limit;
+        };
+        match taker_side {
+            Side::Buy => limit.min(Price(best.0.saturating_add(slippage.0))),
+            Side::Sell => limit.max(Price(best.0.saturating_sub(slippage.0))),
+        }
+    }
 }
 
 /// Executes an incoming order against the book.
@@ -40,75 +350,1197 @@ pub fn execute_match(
     taker_id: OrderId,
     taker_side: Side,
     taker_price: Price,
-    mut taker_qty: Quantity,
+    taker_qty: Quantity,
 ) -> (Quantity, Vec<Trade>) {
-    // Pre-allocate to avoid reallocations during a standard sweep.
-    let mut trades = Vec::with_capacity(AVG_SWEEP_SIZE);
+    execute_match_with(
+        book,
+        taker_id,
+        taker_side,
+        taker_price,
+        taker_qty,
+        TakerConstraints::default(),
+    )
+}
 
-    loop {
-        // 1. FULL FILL CHECK: If incoming is filled, stop matching.
-        if taker_qty.0 == 0 {
-            break;
-        }
+/// [`execute_match`] honouring the taker's [`TakerConstraints`].
+pub fn execute_match_with(
+    book: &mut OrderBook,
+    taker_id: OrderId,
+    taker_side: Side,
+    taker_price: Price,
+    taker_qty: Quantity,
+    constraints: TakerConstraints,
+) -> (Quantity, Vec<Trade>) {
+    // Starts unallocated: `execute_match_into` reserves a full sweep's worth
+    // on the first trade, so purely resting orders never touch the heap.
+    let mut trades = Vec::new();
+    let remaining = execute_match_into(
+        book,
+        taker_id,
+        taker_side,
+        taker_price,
+        taker_qty,
+        constraints,
+        &mut trades,
+    );
+    (remaining, trades)
+}
+
+/// Core matching loop: appends every generated [`Trade`] to `trades`.
+///
+/// Performs no allocation of its own. The only possible allocation is `trades`
+/// growing past its capacity, which callers can rule out up front with
+/// [`match_footprint`]. An empty, unallocated `trades` is given
+/// `AVG_SWEEP_SIZE` capacity on the first push rather than growing one
+/// doubling at a time.
+///
+/// If a trade observer is registered (`OrderBook::on_trade`), it is called
+/// with each trade as it is generated, before the trade is appended.
+///
+/// Returns the taker's remaining (unfilled) quantity.
+pub fn execute_match_into(
+    book: &mut OrderBook,
+    taker_id: OrderId,
+    taker_side: Side,
+    taker_price: Price,
+    taker_qty: Quantity,
+    constraints: TakerConstraints,
+    trades: &mut Vec<Trade>,
+) -> Quantity {
+    let taker = OrderRequest {
+        constraints,
+        account: constraints.account,
+        ..OrderRequest::limit(taker_id.0, taker_side, taker_price.0, taker_qty.0)
+    };
+    match_request(book, &taker, trades)
+}
+
+/// [`execute_match_into`] for a full request: also stamps `req.recv_ts` on
+/// every trade, so observers see the same trades the caller gets back.
+pub(crate) fn match_request(
+    book: &mut OrderBook,
+    req: &OrderRequest,
+    trades: &mut Vec<Trade>,
+) -> Quantity {
+    // Take the observer out so it can be called while the book is borrowed
+    // mutably. It is put back once the loop ends.
+    let mut observer = book.trade_observer.take();
+    let remaining = match book.match_mode {
+        MatchMode::PriceTime => match_price_time(book, req, trades, &mut observer),
+        MatchMode::ProRata => match_pro_rata(book, req, trades, &mut observer),
+    };
+    book.trade_observer = observer;
+    remaining
+}
 
-        // 2. LIQUIDITY CHECK: Get the best order on the OPPOSITE side.
+/// FIFO matching: walks the opposite side maker by maker in priority order.
+fn match_price_time(
+    book: &mut OrderBook,
+    req: &OrderRequest,
+    trades: &mut Vec<Trade>,
+    observer: &mut Option<TradeObserver>,
+) -> Quantity {
+    let mut walk = FifoWalk::new(book, req);
+    while let Some(trade) = walk.next_fill(book, req, observer) {
+        push_trade(trades, trade);
+    }
+    walk.taker_qty
+}
+
+/// Resumable state of a FIFO walk: where it is on the opposite side and how
+/// much of the taker is left. Shared by [`match_price_time`], which runs it to
+/// the end, and [`MatchStream`], which runs it one fill at a time.
+struct FifoWalk {
+    cursor: Option<OrderPtr>,
+    /// The taker's limit, tightened by `max_slippage`. Only bounds the walk:
+    /// trade prices still use the request's own limit.
+    limit: Price,
+    /// Price level at which a maker was skipped (if any). We never trade through it.
+    skipped_level: Option<Price>,
+    taker_qty: Quantity,
+}
+
+impl FifoWalk {
+    fn new(book: &OrderBook, req: &OrderRequest) -> Self {
+        // 1. START AT THE TOP: Get the best order on the OPPOSITE side.
         //    - Buying? Look at the lowest Sell (Best Ask).
         //    - Selling? Look at the highest Buy (Best Bid).
-        let best_match_ptr = match taker_side {
+        //    We walk with a cursor rather than re-reading the head, because skipped
+        //    makers stay in the book ahead of us.
+        let cursor = match req.side {
             Side::Buy => book.best_ask,
             Side::Sell => book.best_bid,
         };
+        let best = cursor.map(|ptr| unsafe { ptr.as_ref().price });
+        Self {
+            cursor,
+            limit: req
+                .taker_constraints()
+                .sweep_limit(req.side, req.price, best),
+            skipped_level: None,
+            taker_qty: req.qty,
+        }
+    }
 
-        // 3. EMPTY BOOK CHECK: If no orders exist, stop matching.
-        //    The remaining quantity will become a resting order.
-        let mut maker_ptr = match best_match_ptr {
-            Some(ptr) => ptr,
-            None => break,
-        };
+    /// Advances to the next maker that trades and fills it.
+    ///
+    /// Returns `None` once matching is over: the taker is filled, the book
+    /// is exhausted or no longer crossed, or self-trade prevention cancelled
+    /// the taker. Every later call returns `None` too.
+    fn next_fill(
+        &mut self,
+        book: &mut OrderBook,
+        req: &OrderRequest,
+        observer: &mut Option<TradeObserver>,
+    ) -> Option<Trade> {
+        let constraints = req.taker_constraints();
+        let stp = book.self_trade_prevention;
 
-        // 4. MEMORY ACCESS
-        // SAFETY: We hold a mutable reference to `book` for the duration of this function.
-        // Rust's borrow rules prevent any other thread or function from modifying the Arena
-        // while we are here. Thus, the pointer retrieved from `best_bid/ask` is guaranteed valid.
-        let maker_order = unsafe { maker_ptr.as_mut() };
-
-        // 5. PRICE CROSSING CHECK (The "Limit" Logic)
-        //    A match only occurs if the Taker's price meets or exceeds the Maker's terms.
-        let crosses = match taker_side {
-            Side::Buy => taker_price >= maker_order.price, // Willing to pay Maker's Ask or more
-            Side::Sell => taker_price <= maker_order.price, // Willing to sell for Maker's Bid or less
-        };
+        loop {
+            // 2. FULL FILL CHECK: If incoming is filled, stop matching.
+            if self.taker_qty.0 == 0 {
+                return None;
+            }
+
+            // 3. EMPTY BOOK CHECK: If no orders remain, stop matching.
+            //    The remaining quantity will become a resting order.
+            let mut maker_ptr = self.cursor?;
+
+            // 4. MEMORY ACCESS
+            // SAFETY: We hold a mutable reference to `book` for the duration of this function.
+            // Rust's borrow rules prevent any other thread or function from modifying the Arena
+            // while we are here. Thus, the pointer retrieved from `best_bid/ask` is guaranteed valid.
+            let maker_order = unsafe { maker_ptr.as_mut() };
 
-        if !crosses {
+            // 5. PRICE CROSSING CHECK (The "Limit" Logic)
             // SPREAD PROTECTION:
             // Since the book is sorted by best price, if we don't cross the top order,
             // we mathematically cannot cross any subsequent orders.
+            // 6. PRICE PRIORITY GUARD: Never move past a level we skipped makers on.
+            if !crosses(req.side, self.limit, maker_order.price)
+                || self
+                    .skipped_level
+                    .is_some_and(|level| level != maker_order.price)
+            {
+                self.cursor = None;
+                return None;
+            }
+
+            // 7. REST MODE CHECK: A `CancelIfCrossed` maker is pulled, not filled.
+            if maker_order.rest_mode == RestMode::CancelIfCrossed {
+                self.cursor = maker_order.next;
+                pull_maker(book, maker_order.id);
+                continue;
+            }
+
+            // 7b. SELF-TRADE PREVENTION: Same account on both sides never trades.
+            if let Some(policy) = self_trade(stp, &constraints, maker_order) {
+                if policy == SelfTradePrevention::CancelIncoming {
+                    self.cursor = None;
+                    return None;
+                }
+                self.cursor = maker_order.next;
+                prevent_self_trade(book, policy, maker_ptr, &mut self.taker_qty);
+                continue;
+            }
+
+            // 8. EXECUTION: Calculate fill quantity (Partial vs Full).
+            //    We can only trade the minimum of what Taker wants and Maker has.
+            let trade_qty = core::cmp::min(self.taker_qty.0, maker_order.qty.0);
+
+            // 9. MINIMUM FILL CHECK: Skip makers too small for this taker,
+            //    unless the fill would complete the taker.
+            if skips_maker(&constraints, trade_qty, self.taker_qty) {
+                self.skipped_level = Some(maker_order.price);
+                self.cursor = maker_order.next;
+                continue;
+            }
+
+            // 10-12. TRADE: Capture `next` first, since a filled maker is unlinked.
+            //     If a refilled iceberg is now the last order at its price, it is
+            //     the next maker at this level; otherwise we reach it after them.
+            self.taker_qty.0 -= trade_qty;
+            let price = maker_order.price;
+            self.cursor = maker_order.next;
+            let (trade, refilled) = fill_maker(book, maker_ptr, trade_qty, req, observer);
+            if refilled
+                && self
+                    .cursor
+                    .is_none_or(|next| unsafe { next.as_ref().price } != price)
+            {
+                self.cursor = Some(maker_ptr);
+            }
+            return Some(trade);
+        }
+    }
+}
+
+/// A taker matched lazily, one maker per [`next`](Iterator::next), returned
+/// by [`OrderBook::match_stream`].
+///
+/// Each `next()` fills the next maker in price-time priority, mutating the
+/// book right away, and yields the resulting [`Trade`]. The book is
+/// consistent between calls: every trade yielded so far is fully applied and
+/// nothing else has happened yet.
+///
+/// The taker never rests. Dropping the stream early (or exhausting it with
+/// quantity left) abandons the rest of the taker, exactly like an IOC
+/// remainder; [`remaining`](Self::remaining) reports how much that is.
+///
+/// The book's trade observer, if any, is called with each trade as it is
+/// yielded and is restored when the stream is dropped.
+pub struct MatchStream<'a> {
+    book: &'a mut OrderBook,
+    req: OrderRequest,
+    walk: FifoWalk,
+    observer: Option<TradeObserver>,
+}
+
+impl<'a> MatchStream<'a> {
+    pub(crate) fn new(book: &'a mut OrderBook, req: OrderRequest) -> Self {
+        let walk = FifoWalk::new(book, &req);
+        let observer = book.trade_observer.take();
+        Self {
+            book,
+            req,
+            walk,
+            observer,
+        }
+    }
+
+    /// Taker quantity not yet filled.
+    pub fn remaining(&self) -> Quantity {
+        self.walk.taker_qty
+    }
+}
+
+impl Iterator for MatchStream<'_> {
+    type Item = Trade;
+
+    fn next(&mut self) -> Option<Trade> {
+        self.walk
+            .next_fill(self.book, &self.req, &mut self.observer)
+    }
+}
+
+impl core::iter::FusedIterator for MatchStream<'_> {}
+
+impl Drop for MatchStream<'_> {
+    fn drop(&mut self) {
+        self.book.trade_observer = self.observer.take();
+    }
+}
+
+/// Pro-rata matching: each crossed price level is shared among its makers in
+/// proportion to their visible size (see [`MatchMode::ProRata`]).
+fn match_pro_rata(
+    book: &mut OrderBook,
+    req: &OrderRequest,
+    trades: &mut Vec<Trade>,
+    observer: &mut Option<TradeObserver>,
+) -> Quantity {
+    let OrderRequest {
+        side: taker_side,
+        price: taker_price,
+        qty: mut taker_qty,
+        ..
+    } = *req;
+    let constraints = req.taker_constraints();
+    let stp = book.self_trade_prevention;
+    let taker_price =
+        constraints.sweep_limit(taker_side, taker_price, opposite_best(book, taker_side));
+
+    // Re-read the head every round: each round either empties the level,
+    // refills its icebergs, or fills the taker.
+    while taker_qty.0 > 0 {
+        let Some(head) = (match taker_side {
+            Side::Buy => book.best_ask,
+            Side::Sell => book.best_bid,
+        }) else {
+            break;
+        };
+        let price = unsafe { head.as_ref().price };
+        if !crosses(taker_side, taker_price, price) {
+            break;
+        }
+
+        // 1. Clear makers that cannot trade, exactly as FIFO matching would
+        //    on reaching them, so the whole level is eligible for sharing.
+        let mut cursor = Some(head);
+        let mut cancel_incoming = false;
+        while let Some(maker_ptr) = cursor {
+            let maker_order = unsafe { maker_ptr.as_ref() };
+            if maker_order.price != price || taker_qty.0 == 0 {
+                break;
+            }
+            cursor = maker_order.next;
+            if maker_order.rest_mode == RestMode::CancelIfCrossed {
+                pull_maker(book, maker_order.id);
+            } else if let Some(policy) = self_trade(stp, &constraints, maker_order) {
+                if policy == SelfTradePrevention::CancelIncoming {
+                    cancel_incoming = true;
+                    break;
+                }
+                prevent_self_trade(book, policy, maker_ptr, &mut taker_qty);
+            }
+        }
+        if cancel_incoming {
+            break;
+        }
+
+        // 2. Share what is left of the taker across the level.
+        let Some(head) = (match taker_side {
+            Side::Buy => book.best_ask,
+            Side::Sell => book.best_bid,
+        }) else {
+            break;
+        };
+        if taker_qty.0 == 0 || unsafe { head.as_ref().price } != price {
+            continue;
+        }
+        let filled = allocate_pro_rata(
+            head,
+            taker_qty.0,
+            |maker| maker.qty.0,
+            |maker_ptr, fill| {
+                let (trade, _) = fill_maker(book, maker_ptr, fill, req, observer);
+                push_trade(trades, trade);
+            },
+        );
+        taker_qty.0 -= filled;
+    }
+
+    taker_qty
+}
+
+/// Splits `taker_qty` across the price level starting at `head`.
+///
+/// **Allocation:** with `L` the sum of `share` over the level and
+/// `T = min(taker_qty, L)`, each maker gets `floor(T * share / L)`. The
+/// rounding remainder goes to the largest maker (the earliest one on a tie),
+/// and whatever does not fit there to the others in book order, so the level
+/// always fills exactly `T`. Computed in `u128` and in three passes over the
+/// level, with no buffer.
+///
+/// `on_fill` is called once per maker with a non-zero fill, in book order.
+/// It may unlink or re-queue that maker: the level's last node is fixed
+/// before the first call and each `next` is read before its call. Returns `T`.
+fn allocate_pro_rata(
+    head: OrderPtr,
+    taker_qty: u64,
+    share: impl Fn(&Order) -> u64,
+    mut on_fill: impl FnMut(OrderPtr, u64),
+) -> u64 {
+    let price = unsafe { head.as_ref().price };
+    let level = || {
+        let mut cursor = Some(head);
+        core::iter::from_fn(move || {
+            let ptr = cursor?;
+            let order = unsafe { ptr.as_ref() };
+            if order.price != price {
+                return None;
+            }
+            cursor = order.next;
+            Some(ptr)
+        })
+    };
+
+    // Pass 1: level total, largest maker and the level's last node.
+    let (mut level_total, mut largest, mut tail) = (0u64, None, head);
+    for ptr in level() {
+        let qty = share(unsafe { ptr.as_ref() });
+        level_total += qty;
+        if largest.is_none_or(|(_, max)| qty > max) {
+            largest = Some((ptr, qty));
+        }
+        tail = ptr;
+    }
+    let Some((largest, _)) = largest.filter(|_| level_total > 0) else {
+        return 0;
+    };
+    let target = taker_qty.min(level_total);
+    let base = |qty: u64| (target as u128 * qty as u128 / level_total as u128) as u64;
+
+    // Pass 2: the rounding remainder, of which the largest maker takes what fits.
+    let mut remainder = target
+        - level()
+            .map(|ptr| base(share(unsafe { ptr.as_ref() })))
+            .sum::<u64>();
+    let largest_qty = share(unsafe { largest.as_ref() });
+    let largest_extra = remainder.min(largest_qty - base(largest_qty));
+    remainder -= largest_extra;
+
+    // Pass 3: fill. Stop at the recorded tail, since a re-queued maker moves
+    // behind it.
+    let mut cursor = Some(head);
+    while let Some(ptr) = cursor {
+        let order = unsafe { ptr.as_ref() };
+        cursor = if ptr == tail { None } else { order.next };
+
+        let qty = share(order);
+        let mut fill = base(qty);
+        if ptr == largest {
+            fill += largest_extra;
+        } else {
+            let extra = remainder.min(qty - fill);
+            remainder -= extra;
+            fill += extra;
+        }
+        if fill > 0 {
+            on_fill(ptr, fill);
+        }
+    }
+
+    target
+}
+
+/// Trades `trade_qty` against a maker: updates it, builds the [`Trade`] (and
+/// hands it to the observer) and removes the maker once fully filled.
+///
+/// Returns the trade, and `true` if the maker was an iceberg whose slice ran
+/// out and was refilled, which re-queues it at the back of its price level.
+fn fill_maker(
+    book: &mut OrderBook,
+    mut maker_ptr: OrderPtr,
+    trade_qty: u64,
+    req: &OrderRequest,
+    observer: &mut Option<TradeObserver>,
+) -> (Trade, bool) {
+    // SAFETY: The caller holds `&mut OrderBook`, so the maker is live and unaliased.
+    let maker_order = unsafe { maker_ptr.as_mut() };
+
+    // 10. UPDATE STATE
+    maker_order.qty.0 -= trade_qty;
+    maker_order.total_qty.0 -= trade_qty;
+    book.version += 1;
+    let price = book.pricing_policy.trade_price(
+        req.side,
+        (!req.market).then_some(req.price),
+        maker_order.price,
+        book.tick_size(),
+    );
+    book.last_trade_price = Some(price);
+    book.last_trade_qty = Some(Quantity(trade_qty));
+    book.cumulative_volume.0 = book.cumulative_volume.0.saturating_add(trade_qty);
+
+    // 11. EVENT GENERATION
+    let seq = book.take_seq();
+    let (maker_fee, taker_fee) = book.fee_schedule.fees(price, Quantity(trade_qty));
+    let trade = Trade {
+        maker_id: maker_order.id,
+        taker_id: req.id,
+        price,
+        quantity: Quantity(trade_qty),
+        maker_side: maker_order.side,
+        maker_leaves_qty: maker_order.total_qty,
+        seq,
+        maker_seq: maker_order.seq,
+        taker_recv_ts: req.recv_ts,
+        maker_fee,
+        taker_fee,
+    };
+    if let Some(observer) = observer.as_mut() {
+        observer(&trade);
+    }
+    book.metrics.trades_generated += 1;
+
+    // 12. CLEANUP: If Maker is fully filled, remove it from the book.
+    //     This updates the Head pointers (best_bid/ask) to the next order in the list,
+    //     drops it from the index and recycles its slot.
+    if maker_order.total_qty.0 == 0 {
+        book.take_order(maker_order.id);
+        return (trade, false);
+    }
+    if maker_order.qty.0 > 0 {
+        return (trade, false);
+    }
+
+    // 12b. ICEBERG REFILL: Show the next slice from the hidden reserve and
+    //      re-queue at the back of the level (priority is lost).
+    let price = maker_order.price;
+    let slice = maker_order.slice_of(maker_order.total_qty);
+    // SAFETY: Re-queued at its own price, which the taker crossed, so it
+    // cannot cross the (opposite-side) resting book.
+    unsafe {
+        book.relink(maker_ptr, price, slice);
+    }
+    (trade, true)
+}
+
+/// Appends a trade. An empty, unallocated `trades` is first given
+/// `AVG_SWEEP_SIZE` capacity, so a sweep does not grow it one doubling at a time.
+#[inline(always)]
+fn push_trade(trades: &mut Vec<Trade>, trade: Trade) {
+    if trades.capacity() == 0 {
+        trades.reserve(AVG_SWEEP_SIZE);
+    }
+    trades.push(trade);
+}
+
+/// Applies a self-trade prevention `policy` (other than `CancelIncoming`) to
+/// a maker from the taker's own account. No trade is generated.
+fn prevent_self_trade(
+    book: &mut OrderBook,
+    policy: SelfTradePrevention,
+    mut maker_ptr: OrderPtr,
+    taker_qty: &mut Quantity,
+) {
+    let maker_order = unsafe { maker_ptr.as_mut() };
+    if policy == SelfTradePrevention::Decrement {
+        let overlap = core::cmp::min(taker_qty.0, maker_order.qty.0);
+        taker_qty.0 -= overlap;
+        maker_order.qty.0 -= overlap;
+        maker_order.total_qty.0 -= overlap;
+        book.version += 1;
+    }
+    if policy == SelfTradePrevention::CancelResting || maker_order.qty.0 == 0 {
+        pull_maker(book, maker_order.id);
+    }
+}
+
+/// Removes a maker without trading and reports it as auto-cancelled.
+fn pull_maker(book: &mut OrderBook, id: OrderId) {
+    book.take_order(id);
+    book.auto_cancelled.push(id);
+}
+
+/// Best price on the side a `taker_side` order trades against.
+#[inline(always)]
+fn opposite_best(book: &OrderBook, taker_side: Side) -> Option<Price> {
+    match taker_side {
+        Side::Buy => book.best_ask_price(),
+        Side::Sell => book.best_bid_price(),
+    }
+}
+
+/// The price-crossing rule shared by every matching path.
+///
+/// A match only occurs if the Taker's price meets or exceeds the Maker's terms.
+#[inline(always)]
+fn crosses(taker_side: Side, taker_price: Price, maker_price: Price) -> bool {
+    match taker_side {
+        Side::Buy => taker_price >= maker_price, // Willing to pay Maker's Ask or more
+        Side::Sell => taker_price <= maker_price, // Willing to sell for Maker's Bid or less
+    }
+}
+
+/// The self-trade rule: `Some(policy)` if prevention is on and the maker
+/// belongs to the taker's (non-zero) account.
+#[inline(always)]
+fn self_trade(
+    stp: Option<SelfTradePrevention>,
+    constraints: &TakerConstraints,
+    maker: &Order,
+) -> Option<SelfTradePrevention> {
+    stp.filter(|_| constraints.account != 0 && constraints.account == maker.account)
+}
+
+/// The minimum-fill rule from [`TakerConstraints::min_fill_per_maker`].
+#[inline(always)]
+fn skips_maker(constraints: &TakerConstraints, trade_qty: u64, taker_qty: Quantity) -> bool {
+    constraints
+        .min_fill_per_maker
+        .is_some_and(|min_fill| trade_qty < min_fill.0 && trade_qty < taker_qty.0)
+}
+
+/// Read-only walk of the fills a taker would receive.
+///
+/// Follows `next` pointers instead of repeatedly reading the head, because
+/// nothing is removed. `on_fill` receives each maker, the fill quantity and
+/// the maker's remaining total after it.
+/// Returns the quantity left unfilled.
+///
+/// **Icebergs:** the real matcher re-queues a refilled slice behind the rest
+/// of its level, so a level trades in rounds: first every maker in queue
+/// order, then each iceberg whose slice ran out, again in queue order (the
+/// order they were re-queued in), and so on. The walk replays those rounds
+/// from each iceberg's state without writing anything (see
+/// [`leaves_before`]), so it reports the same fills, in the same order, as
+/// the real match: one `on_fill` per trade.
+///
+/// # Safety Note
+/// Only shared references into the arena are created here. The `&OrderBook`
+/// borrow guarantees no mutation can happen while the walk is in progress.
+fn walk_fills(
+    book: &OrderBook,
+    taker_side: Side,
+    taker_price: Price,
+    mut taker_qty: Quantity,
+    constraints: TakerConstraints,
+    mut on_fill: impl FnMut(&Order, u64, u64),
+) -> Quantity {
+    let taker_price =
+        constraints.sweep_limit(taker_side, taker_price, opposite_best(book, taker_side));
+    if book.match_mode == MatchMode::ProRata {
+        return walk_pro_rata(
+            book,
+            taker_side,
+            taker_price,
+            taker_qty,
+            constraints,
+            on_fill,
+        );
+    }
+
+    let stp = book.self_trade_prevention;
+    let min_fill = constraints
+        .min_fill_per_maker
+        .map_or(0, |min_fill| min_fill.0);
+    // Whether a maker comes back for refill `round >= 1`: it trades at all,
+    // no earlier slice of it was skipped for `min_fill_per_maker` (every
+    // slice after the first is a full `display_qty`), and reserve is left.
+    let requeued = |maker: &Order, round: u64| {
+        maker.rest_mode == RestMode::Normal
+            && self_trade(stp, &constraints, maker).is_none()
+            && maker.qty.0 >= min_fill
+            && (round == 1 || maker.display_qty.0 >= min_fill)
+            && leaves_before(maker, round) > 0
+    };
+
+    let mut level_head = match taker_side {
+        Side::Buy => book.best_ask,
+        Side::Sell => book.best_bid,
+    };
+    while let Some(head) = level_head {
+        let price = unsafe { head.as_ref().price };
+        if taker_qty.0 == 0 || !crosses(taker_side, taker_price, price) {
+            break;
+        }
+        // A level with a skipped maker is never traded through.
+        let mut skipped = false;
+        let mut refills = false;
+
+        // Round 0: the level as it stands, in queue order.
+        level_head = None;
+        let mut cursor = Some(head);
+        while let Some(maker_ptr) = cursor {
+            let maker_order = unsafe { maker_ptr.as_ref() };
+            if maker_order.price != price {
+                level_head = Some(maker_ptr);
+                break;
+            }
+            cursor = maker_order.next;
+            if taker_qty.0 == 0 {
+                break;
+            }
+
+            // Pulled on contact, never filled.
+            if maker_order.rest_mode == RestMode::CancelIfCrossed {
+                continue;
+            }
+
+            match self_trade(stp, &constraints, maker_order) {
+                Some(SelfTradePrevention::CancelIncoming) => return taker_qty,
+                Some(SelfTradePrevention::CancelResting) => continue,
+                Some(SelfTradePrevention::Decrement) => {
+                    taker_qty.0 -= core::cmp::min(taker_qty.0, maker_order.qty.0);
+                    continue;
+                }
+                None => {}
+            }
+
+            let trade_qty = core::cmp::min(taker_qty.0, maker_order.qty.0);
+            if skips_maker(&constraints, trade_qty, taker_qty) {
+                skipped = true;
+                continue;
+            }
+            taker_qty.0 -= trade_qty;
+            on_fill(maker_order, trade_qty, maker_order.total_qty.0 - trade_qty);
+            refills |= requeued(maker_order, 1);
+        }
+
+        // Later rounds: the icebergs refilled in the round before, now
+        // queued behind everything else at the level.
+        let mut round = 1;
+        while refills && taker_qty.0 > 0 {
+            let mut any = false;
+            let mut cursor = Some(head);
+            while let Some(maker_ptr) = cursor {
+                let maker_order = unsafe { maker_ptr.as_ref() };
+                if maker_order.price != price || taker_qty.0 == 0 {
+                    break;
+                }
+                cursor = maker_order.next;
+                if !requeued(maker_order, round) {
+                    continue;
+                }
+                any = true;
+
+                let leaves = leaves_before(maker_order, round);
+                let slice = maker_order.slice_of(Quantity(leaves)).0;
+                let trade_qty = core::cmp::min(taker_qty.0, slice);
+                if skips_maker(&constraints, trade_qty, taker_qty) {
+                    skipped = true;
+                    continue;
+                }
+                taker_qty.0 -= trade_qty;
+                on_fill(maker_order, trade_qty, leaves - trade_qty);
+            }
+            if !any {
+                break;
+            }
+            round += 1;
+        }
+
+        if skipped {
+            break;
+        }
+    }
+
+    taker_qty
+}
+
+/// An iceberg's remaining total before fill round `round` of its level,
+/// given that every earlier round consumed its whole visible slice: round
+/// `0` sees `total_qty`, and each later one `qty + (round - 1) *
+/// display_qty` less. `0` once the reserve is gone, and always `0` past
+/// round `0` for a plain order.
+#[inline(always)]
+fn leaves_before(maker: &Order, round: u64) -> u64 {
+    match round {
+        0 => maker.total_qty.0,
+        _ => maker
+            .hidden_qty()
+            .0
+            .saturating_sub((round - 1).saturating_mul(maker.display_qty.0)),
+    }
+}
+
+/// [`walk_fills`] for [`MatchMode::ProRata`]: shares each level exactly as
+/// `match_pro_rata` would, round by round.
+///
+/// Nothing is refilled here, so an iceberg's slice for each later round is
+/// derived from its state with [`leaves_before`]: every round but the last
+/// consumes all visible quantity at the level.
+fn walk_pro_rata(
+    book: &OrderBook,
+    taker_side: Side,
+    taker_price: Price,
+    mut taker_qty: Quantity,
+    constraints: TakerConstraints,
+    mut on_fill: impl FnMut(&Order, u64, u64),
+) -> Quantity {
+    let stp = book.self_trade_prevention;
+    let tradable = |maker: &Order| {
+        maker.rest_mode == RestMode::Normal && self_trade(stp, &constraints, maker).is_none()
+    };
+
+    let mut level_head = match taker_side {
+        Side::Buy => book.best_ask,
+        Side::Sell => book.best_bid,
+    };
+
+    while let Some(head) = level_head {
+        let price = unsafe { head.as_ref().price };
+        if taker_qty.0 == 0 || !crosses(taker_side, taker_price, price) {
             break;
         }
 
-        // 6. EXECUTION: Calculate fill quantity (Partial vs Full).
-        //    We can only trade the minimum of what Taker wants and Maker has.
-        let trade_qty = std::cmp::min(taker_qty.0, maker_order.qty.0);
+        // Makers `match_pro_rata` clears before sharing, and the next level.
+        level_head = None;
+        let mut cursor = Some(head);
+        while let Some(maker_ptr) = cursor {
+            let maker_order = unsafe { maker_ptr.as_ref() };
+            if maker_order.price != price {
+                level_head = Some(maker_ptr);
+                break;
+            }
+            cursor = maker_order.next;
+            if taker_qty.0 == 0 || maker_order.rest_mode == RestMode::CancelIfCrossed {
+                continue;
+            }
+            match self_trade(stp, &constraints, maker_order) {
+                Some(SelfTradePrevention::CancelIncoming) => return taker_qty,
+                Some(SelfTradePrevention::Decrement) => {
+                    taker_qty.0 -= core::cmp::min(taker_qty.0, maker_order.qty.0);
+                }
+                _ => {}
+            }
+        }
+
+        let mut round = 0;
+        while taker_qty.0 > 0 {
+            let slice = |maker: &Order| match round {
+                _ if !tradable(maker) => 0,
+                0 => maker.qty.0,
+                _ => maker.slice_of(Quantity(leaves_before(maker, round))).0,
+            };
+            let filled = allocate_pro_rata(head, taker_qty.0, slice, |maker_ptr, fill| {
+                let maker = unsafe { maker_ptr.as_ref() };
+                on_fill(maker, fill, leaves_before(maker, round) - fill);
+            });
+            if filled == 0 {
+                break;
+            }
+            taker_qty.0 -= filled;
+            round += 1;
+        }
+    }
+
+    taker_qty
+}
+
+/// Read-only twin of [`execute_match`]: reports the trades an incoming order
+/// *would* generate against the current book without changing it.
+///
+/// Maker quantities are never written; each `Trade::maker_leaves_qty` is the
+/// value the maker would have after the fill. `Trade::seq` is always `0`.
+pub fn simulate_match(
+    book: &OrderBook,
+    taker_id: OrderId,
+    taker_side: Side,
+    taker_price: Price,
+    taker_qty: Quantity,
+) -> (Quantity, Vec<Trade>) {
+    let mut trades = Vec::with_capacity(AVG_SWEEP_SIZE);
+
+    let remaining = walk_fills(
+        book,
+        taker_side,
+        taker_price,
+        taker_qty,
+        TakerConstraints::default(),
+        |maker, fill, leaves| {
+            let price = book.pricing_policy.trade_price(
+                taker_side,
+                Some(taker_price),
+                maker.price,
+                book.tick_size(),
+            );
+            let (maker_fee, taker_fee) = book.fee_schedule.fees(price, Quantity(fill));
+            trades.push(Trade {
+                maker_id: maker.id,
+                taker_id,
+                price,
+                quantity: Quantity(fill),
+                maker_side: maker.side,
+                maker_leaves_qty: Quantity(leaves),
+                seq: 0, // Hypothetical: no sequence number is consumed
+                maker_seq: maker.seq,
+                taker_recv_ts: None,
+                maker_fee,
+                taker_fee,
+            });
+        },
+    );
+
+    (remaining, trades)
+}
+
+/// Allocation-free dry run returning `(remaining_qty, trade_count)`.
+///
+/// Lets a caller size (or reject) a trade buffer and check for a free slot
+/// before committing to [`execute_match_into`].
+pub fn match_footprint(
+    book: &OrderBook,
+    taker_side: Side,
+    taker_price: Price,
+    taker_qty: Quantity,
+    constraints: TakerConstraints,
+) -> (Quantity, usize) {
+    let mut trade_count = 0;
+    let remaining = walk_fills(
+        book,
+        taker_side,
+        taker_price,
+        taker_qty,
+        constraints,
+        |_, _, _| trade_count += 1,
+    );
+    (remaining, trade_count)
+}
+
+/// Read-only fill-or-kill check: `true` if `taker_qty` would be filled in full
+/// against the book as it stands.
+///
+/// Sums fills across as many price levels as needed (partial makers count
+/// for what they hold), without touching any maker. Resting quantity exactly
+/// equal to `taker_qty` counts as fillable.
+pub fn can_fill(
+    book: &OrderBook,
+    taker_side: Side,
+    taker_price: Price,
+    taker_qty: Quantity,
+) -> bool {
+    can_fill_with(
+        book,
+        taker_side,
+        taker_price,
+        taker_qty,
+        TakerConstraints::default(),
+    )
+}
+
+/// [`can_fill`] for a taker that honours `constraints` (skipped makers do not
+/// count towards the fill).
+pub fn can_fill_with(
+    book: &OrderBook,
+    taker_side: Side,
+    taker_price: Price,
+    taker_qty: Quantity,
+    constraints: TakerConstraints,
+) -> bool {
+    walk_fills(
+        book,
+        taker_side,
+        taker_price,
+        taker_qty,
+        constraints,
+        |_, _, _| {},
+    )
+    .0 == 0
+}
+
+/// Read-only volume-weighted average price a market taker would pay (buy) or
+/// receive (sell) for `taker_qty`, following the same crossing and FIFO walk
+/// as [`execute_match`].
+///
+/// Returns `None` if the opposite side cannot fill the whole quantity (or
+/// `taker_qty` is zero). The average is computed in `u128` and **rounds
+/// down** (truncating division) to the nearest price tick. The notional
+/// cannot overflow: it is at most `u64::MAX * taker_qty`, which fits.
+pub fn vwap(book: &OrderBook, taker_side: Side, taker_qty: Quantity) -> Option<Price> {
+    let market_price = match taker_side {
+        Side::Buy => Price(u64::MAX),
+        Side::Sell => Price(0),
+    };
+    let mut notional: u128 = 0;
+    let remaining = walk_fills(
+        book,
+        taker_side,
+        market_price,
+        taker_qty,
+        TakerConstraints::default(),
+        |maker, fill, _| notional += maker.price.0 as u128 * fill as u128,
+    );
+
+    if taker_qty.0 == 0 || remaining.0 > 0 {
+        return None;
+    }
+    Some(Price((notional / taker_qty.0 as u128) as u64))
+}
+
+/// Single-price batch match of a crossed or locked book. See
+/// `OrderBook::uncross`.
+pub(crate) fn uncross(book: &mut OrderBook) -> (Option<Price>, Vec<Trade>) {
+    let Some((price, volume)) = clearing_price(book) else {
+        return (None, Vec::new());
+    };
+
+    let mut observer = book.trade_observer.take();
+    let mut trades = Vec::with_capacity(AVG_SWEEP_SIZE);
+    let mut remaining = volume;
+    // Both heads are always eligible: `volume` never exceeds what rests at
+    // or through `price` on either side, and the heads are reached in
+    // priority (so price) order.
+    while remaining > 0
+        && let (Some(bid_ptr), Some(ask_ptr)) = (book.best_bid, book.best_ask)
+    {
+        // SAFETY: The caller holds `&mut OrderBook`, so both heads are live.
+        let (bid, ask) = unsafe { (bid_ptr.as_ref(), ask_ptr.as_ref()) };
+        let fill = remaining.min(bid.total_qty.0).min(ask.total_qty.0);
+
+        // No order is the aggressor in an auction: the later arrival takes
+        // the taker role, as it would have in continuous trading.
+        let (maker, taker) = if (bid.seq, bid.id.0) < (ask.seq, ask.id.0) {
+            (bid, ask)
+        } else {
+            (ask, bid)
+        };
+        let (maker_fee, taker_fee) = book.fee_schedule.fees(price, Quantity(fill));
+        let mut trade = Trade {
+            maker_id: maker.id,
+            taker_id: taker.id,
+            price,
+            quantity: Quantity(fill),
+            maker_side: maker.side,
+            maker_leaves_qty: Quantity(maker.total_qty.0 - fill),
+            seq: 0,
+            maker_seq: maker.seq,
+            taker_recv_ts: None,
+            maker_fee,
+            taker_fee,
+        };
+
+        reduce_resting(book, bid_ptr, fill);
+        reduce_resting(book, ask_ptr, fill);
+        remaining -= fill;
+
+        book.last_trade_price = Some(price);
+        book.last_trade_qty = Some(Quantity(fill));
+        book.cumulative_volume.0 = book.cumulative_volume.0.saturating_add(fill);
+        trade.seq = book.take_seq();
+        if let Some(observer) = observer.as_mut() {
+            observer(&trade);
+        }
+        trades.push(trade);
+        book.metrics.trades_generated += 1;
+    }
+    book.trade_observer = observer;
+
+    (Some(price), trades)
+}
 
-        // 7. EVENT GENERATION
-        trades.push(Trade {
-            maker_id: maker_order.id,
-            taker_id,
-            price: maker_order.price, // Trade price is determined by the resting order (Maker)
-            quantity: Quantity(trade_qty),
-            maker_side: maker_order.side,
-        });
+/// The auction price and the volume that executes at it, or `None` if the
+/// book is not crossed or locked.
+///
+/// Every price level in the crossed region is a candidate. The winner
+/// executes the most volume; ties go to the smallest surplus (unmatched
+/// quantity on the heavier side), then to the lowest price. Levels are
+/// summed by `total_qty`, so iceberg reserves take part.
+fn clearing_price(book: &OrderBook) -> Option<(Price, u64)> {
+    let (Some(best_bid), Some(best_ask)) = book.best_prices() else {
+        return None;
+    };
+    if best_bid < best_ask {
+        return None;
+    }
 
-        // 8. UPDATE STATE
-        taker_qty.0 -= trade_qty;
-        maker_order.qty.0 -= trade_qty;
+    // Aggregated levels that can trade at all, best price first.
+    let crossed_levels = |side: Side| {
+        let mut levels: Vec<(Price, u64)> = Vec::new();
+        for order in book.iter_side(side) {
+            let crossed = match side {
+                Side::Buy => order.price >= best_ask,
+                Side::Sell => order.price <= best_bid,
+            };
+            if !crossed {
+                break;
+            }
+            match levels.last_mut() {
+                Some((price, qty)) if *price == order.price => *qty += order.total_qty.0,
+                _ => levels.push((order.price, order.total_qty.0)),
+            }
+        }
+        levels
+    };
+    let (bids, asks) = (crossed_levels(Side::Buy), crossed_levels(Side::Sell));
 
-        // 9. CLEANUP: If Maker is fully filled, remove it from the book.
-        //    This updates the Head pointers (best_bid/ask) to the next order in the list.
-        if maker_order.qty.0 == 0 {
-            book.remove_order(maker_ptr);
+    // Ranked by (volume, smaller surplus, lower price).
+    let mut best: Option<(u128, Reverse<u128>, Reverse<Price>)> = None;
+    for &(price, _) in bids.iter().chain(&asks) {
+        let demand: u128 = bids
+            .iter()
+            .filter(|(p, _)| *p >= price)
+            .map(|&(_, q)| q as u128)
+            .sum();
+        let supply: u128 = asks
+            .iter()
+            .filter(|(p, _)| *p <= price)
+            .map(|&(_, q)| q as u128)
+            .sum();
+        let rank = (
+            demand.min(supply),
+            Reverse(demand.abs_diff(supply)),
+            Reverse(price),
+        );
+        if best.is_none_or(|best| rank > best) {
+            best = Some(rank);
         }
     }
 
-    (taker_qty, trades)
+    // Saturates only past `u64::MAX` units of resting quantity.
+    best.map(|(volume, _, Reverse(price))| (price, volume.min(u64::MAX as u128) as u64))
+}
+
+/// Takes `fill` off a resting order's total, visible slice first. The order
+/// is removed once empty; an iceberg whose slice runs out is refilled and
+/// re-queued at the back of its level, as in continuous matching.
+fn reduce_resting(book: &mut OrderBook, mut ptr: OrderPtr, fill: u64) {
+    // SAFETY: The caller holds `&mut OrderBook`, so the order is live and unaliased.
+    let order = unsafe { ptr.as_mut() };
+    order.total_qty.0 -= fill;
+    book.version += 1;
+
+    if order.total_qty.0 == 0 {
+        book.take_order(order.id);
+    } else if fill < order.qty.0 {
+        order.qty.0 -= fill;
+    } else {
+        let (price, slice) = (order.price, order.slice_of(order.total_qty));
+        // SAFETY: Re-queued at its own price on its own side.
+        unsafe { book.relink(ptr, price, slice) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A book whose best ask level holds one order per entry of `sizes`, in
+    /// that time priority.
+    fn ask_level(sizes: &[u64]) -> OrderBook {
+        let mut book = OrderBook::new("TEST", 16);
+        for (id, &qty) in sizes.iter().enumerate() {
+            book.place_limit_order(id as u64, Side::Sell, 100, qty)
+                .unwrap();
+        }
+        book
+    }
+
+    /// `allocate_pro_rata` over the best ask level, without touching it:
+    /// `(maker id, fill)` in the order `on_fill` saw them, and the total.
+    fn allocate(book: &OrderBook, taker_qty: u64) -> (Vec<(u64, u64)>, u64) {
+        let mut fills = Vec::new();
+        let total = allocate_pro_rata(
+            book.best_ask.unwrap(),
+            taker_qty,
+            |maker| maker.qty.0,
+            |ptr, fill| fills.push((unsafe { ptr.as_ref() }.id.0, fill)),
+        );
+        (fills, total)
+    }
+
+    #[test]
+    fn pro_rata_splits_in_proportion_to_size() {
+        let book = ask_level(&[10, 30, 60]);
+        let (fills, total) = allocate(&book, 50);
+        assert_eq!(fills, [(0, 5), (1, 15), (2, 30)]);
+        assert_eq!(total, 50);
+    }
+
+    #[test]
+    fn pro_rata_rounding_dust_goes_to_the_largest() {
+        let book = ask_level(&[10, 30, 60]);
+        // Floors are 0, 2 and 4: one lot of dust, which the 60 takes.
+        let (fills, total) = allocate(&book, 7);
+        assert_eq!(fills, [(1, 2), (2, 5)]);
+        assert_eq!(total, 7);
+
+        // On a tie for largest, the earlier order takes the dust.
+        let book = ask_level(&[20, 20, 20]);
+        let (fills, total) = allocate(&book, 4);
+        assert_eq!(fills, [(0, 2), (1, 1), (2, 1)]);
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn pro_rata_fills_exactly_the_taker_or_the_level() {
+        for sizes in [&[1, 1, 1][..], &[7, 13, 29, 3], &[1_000, 1], &[5]] {
+            let book = ask_level(sizes);
+            let level: u64 = sizes.iter().sum();
+            for taker in 1..=level + 3 {
+                let (fills, total) = allocate(&book, taker);
+                let filled: u64 = fills.iter().map(|&(_, fill)| fill).sum();
+                assert_eq!(total, taker.min(level));
+                assert_eq!(filled, total);
+                for &(id, fill) in &fills {
+                    assert!(fill <= sizes[id as usize]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pro_rata_book_trades_the_split() {
+        let mut book = ask_level(&[10, 30, 60]);
+        book.set_match_mode(MatchMode::ProRata);
+        let (_, trades) = book.place_limit_order(9, Side::Buy, 100, 7).unwrap();
+        let fills: Vec<_> = trades
+            .iter()
+            .map(|t| (t.maker_id.0, t.quantity.0))
+            .collect();
+        assert_eq!(fills, [(1, 2), (2, 5)]);
+        assert_eq!(book.total_volume(Side::Sell), Quantity(93));
+    }
 }
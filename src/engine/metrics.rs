@@ -0,0 +1,58 @@
+/// Running operation counters kept by every [`OrderBook`], read with
+/// [`OrderBook::metrics`].
+///
+/// Plain `u64`s bumped on the engine thread: no atomics, no allocation and
+/// no metrics crate. Counters only ever grow (wrapping after `u64::MAX`
+/// events is not a practical concern) and survive
+/// [`clear`](crate::engine::book::OrderBook::clear). Copy the struct out and
+/// diff two samples for rates.
+///
+/// [`OrderBook`]: crate::engine::book::OrderBook
+/// [`OrderBook::metrics`]: crate::engine::book::OrderBook::metrics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Orders accepted for matching, whether they filled, rested or were
+    /// cancelled unfilled. Includes triggered stops and a modify that
+    /// crosses (re-submitted as a fresh order). Rejections are not counted.
+    pub orders_placed: u64,
+    /// Orders removed by an explicit cancel (`cancel_order`, `cancel_side`,
+    /// `cancel_where`, `cancel_and_take`, `expire_orders`, or a modify to
+    /// zero), including parked stops. Fills and matcher-side auto-cancels
+    /// are not counted.
+    pub orders_canceled: u64,
+    /// Amendments that changed a resting order. Idempotent no-op modifies
+    /// and modifies to zero (counted as cancels) are not.
+    pub orders_modified: u64,
+    /// `Trade`s generated, by continuous matching or `uncross`.
+    pub trades_generated: u64,
+    /// Order slots taken from the free list instead of fresh arena memory.
+    pub free_list_reuses: u64,
+}
+
+/// Order-slot usage of an [`OrderBook`]'s arena, read with
+/// [`OrderBook::arena_stats`].
+///
+/// `allocated_slots` is the high-water mark of resting orders over the
+/// book's lifetime: under steady churn it plateaus while placements keep
+/// climbing, with every new order landing in a recycled slot. It survives
+/// [`clear`](crate::engine::book::OrderBook::clear) and ignores the
+/// placeholder slots written by
+/// [`prewarm`](crate::engine::book::OrderBook::prewarm), so
+/// `live_slots + free_slots` may differ from it.
+///
+/// [`OrderBook`]: crate::engine::book::OrderBook
+/// [`OrderBook::arena_stats`]: crate::engine::book::OrderBook::arena_stats
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ArenaStats {
+    /// Most orders ever resting at once: the slots the book has needed.
+    pub allocated_slots: usize,
+    /// Slots holding a resting order.
+    pub live_slots: usize,
+    /// Slots on the free list, waiting to be reused.
+    pub free_slots: usize,
+    /// Share of slot allocations that landed in a slot an earlier order had
+    /// used: `(allocations - allocated_slots) / allocations`, `0.0` before
+    /// the first. Only orders that rest take a slot, so fully filled takers
+    /// count toward neither side.
+    pub reuse_ratio: f64,
+}
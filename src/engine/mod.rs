@@ -1,2 +1,12 @@
 pub mod book;
+pub mod candles;
+pub mod depth;
+pub mod error;
+pub(crate) mod levels;
+pub mod manager;
 pub mod matcher;
+pub mod metrics;
+pub mod publish;
+pub mod rate_limit;
+pub mod request;
+pub mod view;
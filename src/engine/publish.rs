@@ -0,0 +1,95 @@
+#[cfg(target_has_atomic = "64")]
+use core::hint;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering, fence};
+
+/// A plain-old-data snapshot of the top of book, built for lock-free publishing.
+///
+/// Prices and sizes are raw fixed-point `u64`s (see `Price` / `Quantity`), and
+/// an empty side is encoded as `0` price and `0` size so the struct stays `Copy`
+/// with no `Option` discriminants. `seq` is the book's
+/// [`version`](crate::engine::book::OrderBook::version) when the snapshot was taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct TopOfBook {
+    pub bid_px: u64,
+    pub bid_sz: u64,
+    pub ask_px: u64,
+    pub ask_sz: u64,
+    pub seq: u64,
+}
+
+/// A single-writer, multi-reader seqlock holding the latest [`TopOfBook`].
+///
+/// **Usage Pattern:**
+/// 1. The engine thread calls [`publish`](Self::publish) with
+///    `book.pack_top()` after each mutation (or each batch).
+/// 2. Any number of readers call [`read`](Self::read), which never blocks the
+///    writer: it spins only while a write is in progress.
+///
+/// **Protocol:**
+/// The writer bumps `version` to an odd value, stores the fields, then bumps it
+/// to the next even value. A reader samples `version`, copies the fields, and
+/// samples again; if the first sample was odd or the two differ, it raced a
+/// write and retries. Each field is its own `AtomicU64`, so a torn read is
+/// detected rather than undefined behaviour.
+///
+/// Only one thread may call `publish` at a time. Needs 64-bit atomics, so it
+/// is missing on targets without them (e.g. 32-bit Cortex-M).
+#[cfg(target_has_atomic = "64")]
+#[derive(Debug, Default)]
+pub struct TopOfBookCell {
+    version: AtomicU64,
+    bid_px: AtomicU64,
+    bid_sz: AtomicU64,
+    ask_px: AtomicU64,
+    ask_sz: AtomicU64,
+    seq: AtomicU64,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl TopOfBookCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes a new snapshot. Wait-free; must only be called by one writer.
+    pub fn publish(&self, top: TopOfBook) {
+        let v = self.version.load(Ordering::Relaxed);
+        self.version.store(v.wrapping_add(1), Ordering::Relaxed); // Odd: write in progress
+        fence(Ordering::Release);
+
+        self.bid_px.store(top.bid_px, Ordering::Relaxed);
+        self.bid_sz.store(top.bid_sz, Ordering::Relaxed);
+        self.ask_px.store(top.ask_px, Ordering::Relaxed);
+        self.ask_sz.store(top.ask_sz, Ordering::Relaxed);
+        self.seq.store(top.seq, Ordering::Relaxed);
+
+        self.version.store(v.wrapping_add(2), Ordering::Release); // Even: stable
+    }
+
+    /// Returns the latest complete snapshot, retrying while a write is in flight.
+    pub fn read(&self) -> TopOfBook {
+        loop {
+            let before = self.version.load(Ordering::Acquire);
+            if before & 1 == 1 {
+                hint::spin_loop();
+                continue;
+            }
+
+            let top = TopOfBook {
+                bid_px: self.bid_px.load(Ordering::Relaxed),
+                bid_sz: self.bid_sz.load(Ordering::Relaxed),
+                ask_px: self.ask_px.load(Ordering::Relaxed),
+                ask_sz: self.ask_sz.load(Ordering::Relaxed),
+                seq: self.seq.load(Ordering::Relaxed),
+            };
+
+            fence(Ordering::Acquire);
+            if self.version.load(Ordering::Relaxed) == before {
+                return top;
+            }
+            hint::spin_loop();
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use hashbrown::HashMap;
+use rustc_hash::FxBuildHasher;
+
+/// Token-bucket parameters applied to every account independently.
+///
+/// An account may burst up to `burst` orders, after which it earns one new
+/// token every `refill_interval` time units (e.g. `burst = 100`,
+/// `refill_interval = 10_000_000` ns ≈ 100 orders/second sustained).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub refill_interval: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: u32,
+    last_refill: u64,
+}
+
+/// Per-account token buckets keyed by `OrderRequest::account`.
+///
+/// Time is always supplied by the caller, never read from a clock, so the
+/// limiter is deterministic under replay.
+///
+/// **Allocation Note:**
+/// A bucket is inserted the first time an account is seen. Steady-state
+/// traffic from known accounts is allocation-free.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: HashMap<u32, TokenBucket, FxBuildHasher>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: HashMap::default(),
+        }
+    }
+
+    /// Takes one token from `account`'s bucket at time `now`.
+    ///
+    /// Returns `false` (and takes nothing) if the bucket is empty.
+    /// New accounts start with a full bucket.
+    pub fn try_acquire(&mut self, account: u32, now: u64) -> bool {
+        let limit = self.limit;
+        let bucket = self.buckets.entry(account).or_insert(TokenBucket {
+            tokens: limit.burst,
+            last_refill: now,
+        });
+
+        // REFILL: Credit whole intervals only, carrying the partial one forward.
+        let elapsed = now.saturating_sub(bucket.last_refill);
+        let earned = elapsed.checked_div(limit.refill_interval).unwrap_or(0);
+        if earned > 0 {
+            let tokens = (bucket.tokens as u64)
+                .saturating_add(earned)
+                .min(limit.burst as u64);
+            bucket.tokens = tokens as u32;
+            bucket.last_refill = if bucket.tokens == limit.burst {
+                now
+            } else {
+                bucket.last_refill + earned * limit.refill_interval
+            };
+        }
+
+        if bucket.tokens == 0 {
+            return false;
+        }
+        bucket.tokens -= 1;
+        true
+    }
+}
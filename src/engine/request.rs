@@ -0,0 +1,213 @@
+use crate::engine::matcher::TakerConstraints;
+use crate::storage::layout::{OrderId, OrderPtr, OrderView, Price, Quantity, RestMode, Side};
+
+/// How long an order's unfilled remainder may stay in the book.
+///
+/// Mirrors FIX `TimeInForce` (tag 59).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TimeInForce {
+    /// Good-till-cancel: the remainder rests until filled or cancelled.
+    #[default]
+    Gtc = 0,
+    /// Immediate-or-cancel: match what is possible now, cancel the remainder.
+    Ioc = 3,
+    /// Fill-or-kill: fill the entire quantity now, or do nothing at all.
+    Fok = 4,
+}
+
+/// An order-entry message, as received from a gateway.
+///
+/// **Valid Combinations:**
+///
+/// | `tif`         | `post_only` | Behaviour                                         |
+/// | :------------ | :---------- | :------------------------------------------------ |
+/// | `Gtc`         | `false`     | Match, rest the remainder                         |
+/// | `Gtc`         | `true`      | Rest only; rejected if it would match on arrival  |
+/// | `Ioc`         | `false`     | Match, cancel the remainder                       |
+/// | `Fok`         | `false`     | Match fully, or cancel with no trades             |
+/// | `Ioc` / `Fok` | `true`      | Rejected: a post-only order can never take        |
+///
+/// `rest_mode` only matters for orders that can rest (`Gtc`). The same goes
+/// for `display_qty`, so an iceberg `Ioc` or `Fok` is rejected too.
+///
+/// Plain `Copy` data: building or passing a request never allocates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderRequest {
+    pub id: OrderId,
+    pub side: Side,
+    pub price: Price,
+    pub qty: Quantity,
+    /// Restrictions on how this order takes liquidity.
+    pub constraints: TakerConstraints,
+    /// Account or session the order was entered under (`0` if unspecified).
+    pub account: u32,
+    /// Caller-supplied entry time, used for rate limiting.
+    pub timestamp: u64,
+    /// When the order arrived at the gateway, supplied by the caller (the book
+    /// never reads a clock). Purely informational: ignored by matching and
+    /// copied to every resulting `Trade::taker_recv_ts` for latency analysis.
+    pub recv_ts: Option<u64>,
+    /// How the remainder behaves once it rests. See [`RestMode`].
+    pub rest_mode: RestMode,
+    /// Good-till-time for the resting remainder (`None` = until cancelled).
+    /// See `OrderBook::expire_orders`.
+    pub expiry: Option<u64>,
+    pub tif: TimeInForce,
+    /// Maker-only: the order must rest without taking any liquidity.
+    pub post_only: bool,
+    /// Iceberg slice size (`0` = fully displayed). The whole `qty` takes
+    /// liquidity on arrival; only a slice of at most this much of the
+    /// remainder is shown once it rests. See `OrderBook::place_iceberg`.
+    pub display_qty: Quantity,
+    /// No limit of its own: `price` is only the bound the sweep runs to (set
+    /// by `OrderBook::place_market_order`). Such an order always trades at
+    /// the maker's price, whatever the book's `PricingPolicy`.
+    pub market: bool,
+}
+
+impl OrderRequest {
+    /// A plain limit order using the raw integer representation.
+    pub fn limit(id: u64, side: Side, price: u64, qty: u64) -> Self {
+        Self {
+            id: OrderId(id),
+            side,
+            price: Price(price),
+            qty: Quantity(qty),
+            constraints: TakerConstraints::default(),
+            account: 0,
+            timestamp: 0,
+            recv_ts: None,
+            rest_mode: RestMode::Normal,
+            expiry: None,
+            tif: TimeInForce::Gtc,
+            post_only: false,
+            display_qty: Quantity(0),
+            market: false,
+        }
+    }
+
+    /// `constraints` with the taker's `account` filled in for self-trade
+    /// prevention.
+    pub fn taker_constraints(&self) -> TakerConstraints {
+        TakerConstraints {
+            account: self.account,
+            ..self.constraints
+        }
+    }
+
+    /// `true` if the flags contradict each other (see the table above).
+    pub fn is_contradictory(&self) -> bool {
+        (self.post_only || self.display_qty.0 > 0) && self.tif != TimeInForce::Gtc
+    }
+}
+
+/// Re-creates a taken order (see `OrderBook::cancel_and_take`) as a `Gtc`
+/// limit request for its whole remaining quantity, keeping its iceberg
+/// slice, rest mode, expiry and account.
+///
+/// `placed_at` becomes the `timestamp`, so a book without a clock stamps the
+/// re-placed order with its original time. Priority is not carried over: it
+/// queues behind whatever already rests at its price, and an iceberg shows a
+/// full slice again.
+impl From<OrderView> for OrderRequest {
+    fn from(view: OrderView) -> Self {
+        Self {
+            account: view.account,
+            timestamp: view.placed_at,
+            rest_mode: view.rest_mode,
+            expiry: view.expiry,
+            display_qty: view.display_qty,
+            ..Self::limit(view.id.0, view.side, view.price.0, view.total_qty.0)
+        }
+    }
+}
+
+/// The result of a successful placement.
+///
+/// Trades are reported separately (in the caller's buffer), so this stays a
+/// small `Copy` value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementOutcome {
+    /// The order was completely filled on entry. Nothing rests in the book.
+    Filled,
+    /// The unfilled remainder now rests in the book at this slot.
+    Resting(OrderPtr),
+    /// Part or all of the order was left unfilled and cancelled instead of
+    /// resting (IOC remainder, killed FOK, or a remainder that skipped makers
+    /// would have crossed).
+    Cancelled,
+}
+
+/// Quantity rules every new order must satisfy, set per book with
+/// `OrderBook::set_quantity_limits`.
+///
+/// The default (`lot_size = 1`, `min_qty = 1`, `max_qty = u64::MAX`) accepts
+/// any non-zero quantity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuantityLimits {
+    /// Quantities must be a multiple of this (`0` is treated as `1`).
+    pub lot_size: u64,
+    pub min_qty: u64,
+    pub max_qty: u64,
+}
+
+impl Default for QuantityLimits {
+    fn default() -> Self {
+        Self {
+            lot_size: 1,
+            min_qty: 1,
+            max_qty: u64::MAX,
+        }
+    }
+}
+
+impl QuantityLimits {
+    /// `true` if `qty` is non-zero, a whole number of lots and within
+    /// `[min_qty, max_qty]`.
+    pub fn allows(&self, qty: u64) -> bool {
+        qty > 0
+            && qty.is_multiple_of(self.lot_size.max(1))
+            && (self.min_qty..=self.max_qty).contains(&qty)
+    }
+}
+
+/// What a request-based modify ([`OrderBook::modify`]) does when the target
+/// order is no longer in the book (e.g. it was fully filled a moment earlier).
+///
+/// [`OrderBook::modify`]: crate::engine::book::OrderBook::modify
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModifyMissingPolicy {
+    /// Reject with `OrderBookError::OrderNotFound` (the conventional behaviour).
+    #[default]
+    Reject,
+    /// Treat the modify as a new placement ("modify = upsert").
+    PlaceNew,
+}
+
+/// One journaled book mutation, as applied by [`OrderBook::apply`].
+///
+/// Matching is fully deterministic (no clocks, no randomness, no hash-order
+/// dependence), so replaying the same commands on a fresh book with the same
+/// settings reproduces the same book, trade for trade.
+///
+/// [`OrderBook::apply`]: crate::engine::book::OrderBook::apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// A plain GTC limit order.
+    Place {
+        id: OrderId,
+        side: Side,
+        price: Price,
+        qty: Quantity,
+    },
+    Cancel {
+        id: OrderId,
+    },
+    /// Cancel/replace of a resting order (see `OrderBook::modify_order`).
+    Modify {
+        id: OrderId,
+        price: Price,
+        qty: Quantity,
+    },
+}
@@ -0,0 +1,69 @@
+use crate::engine::book::{OrderBook, OrderIter};
+use crate::storage::layout::{Price, Quantity, Side};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// Type-level tag for one side of the book.
+pub trait SideMarker {
+    const SIDE: Side;
+}
+
+/// Marker for the bid side (see [`OrderBook::bids`]).
+pub struct Buy;
+
+/// Marker for the ask side (see [`OrderBook::asks`]).
+pub struct Sell;
+
+impl SideMarker for Buy {
+    const SIDE: Side = Side::Buy;
+}
+
+impl SideMarker for Sell {
+    const SIDE: Side = Side::Sell;
+}
+
+/// A read-only view of one side of the book, with the side fixed in the type.
+///
+/// **Zero-Cost Abstraction:**
+/// A `SideView` is just a `&OrderBook` plus a zero-sized marker. Every method
+/// forwards to the side-parameterized `OrderBook` method with `S::SIDE`, a
+/// compile-time constant, so the wrapper compiles away entirely. What it buys
+/// is that a function taking `SideView<'_, Buy>` cannot be handed the asks.
+pub struct SideView<'a, S: SideMarker> {
+    book: &'a OrderBook,
+    _side: PhantomData<S>,
+}
+
+impl<'a, S: SideMarker> SideView<'a, S> {
+    pub(crate) fn new(book: &'a OrderBook) -> Self {
+        Self {
+            book,
+            _side: PhantomData,
+        }
+    }
+
+    /// The side this view is fixed to.
+    pub fn side(&self) -> Side {
+        S::SIDE
+    }
+
+    /// Best price level and the total quantity resting there.
+    pub fn best(&self) -> Option<(Price, Quantity)> {
+        self.book.top_level(S::SIDE)
+    }
+
+    /// Aggregated ladder of the top `levels` prices, best first.
+    pub fn depth(&self, levels: usize) -> Vec<(Price, Quantity)> {
+        self.book.side_depth(S::SIDE, levels)
+    }
+
+    /// Total quantity resting on this side.
+    pub fn total_volume(&self) -> Quantity {
+        self.book.total_volume(S::SIDE)
+    }
+
+    /// Every resting order on this side in priority order.
+    pub fn iter(&self) -> OrderIter<'a> {
+        self.book.iter_side(S::SIDE)
+    }
+}
@@ -1,3 +1,11 @@
+//! With the default `std` feature off, the crate is `#![no_std]` and needs
+//! only `alloc`. Left out are the `std::io` CSV helpers and the debugging
+//! aids that format into `String`s (`OrderBook::validate` and the book's
+//! `Display` ladder). `TopOfBookCell` also needs 64-bit atomics.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod engine;
 pub mod storage;
 
@@ -0,0 +1,76 @@
+//! Fixed-size order storage that needs only `alloc`.
+
+use alloc::boxed::Box;
+use core::mem::{self, MaybeUninit};
+use core::ptr::{self, NonNull};
+
+use super::layout::Order;
+
+/// A bump allocator over one heap block of `Order` slots.
+///
+/// The block is held as a raw pointer rather than a `Box` so that moving the
+/// arena (and the book that owns it) never asserts unique ownership of the
+/// memory the book's `OrderPtr`s point into. Slots are handed out in order
+/// and only given back all at once by `reset`; `Order` has no destructor, so
+/// nothing is dropped.
+pub(crate) struct Arena {
+    slots: NonNull<MaybeUninit<Order>>,
+    len: usize,
+    used: usize,
+}
+
+impl Arena {
+    /// Allocates room for as many orders as fit in `capacity_bytes`.
+    pub(crate) fn new(capacity_bytes: usize) -> Self {
+        let len = capacity_bytes / mem::size_of::<Order>();
+        let block = Box::<[Order]>::new_uninit_slice(len);
+        let slots = NonNull::new(Box::into_raw(block).cast::<MaybeUninit<Order>>())
+            .expect("Box::into_raw never returns null");
+        Self {
+            slots,
+            len,
+            used: 0,
+        }
+    }
+
+    /// Moves `order` into the next free slot, or returns `None` if the arena
+    /// is full.
+    pub(crate) fn alloc(&mut self, order: Order) -> Option<NonNull<Order>> {
+        if self.used == self.len {
+            return None;
+        }
+        // SAFETY: `used < len`, so the slot lies inside the block, and it is
+        // written through the block's own pointer without touching any slot
+        // handed out before.
+        let slot = unsafe { self.slots.as_ptr().add(self.used) }.cast::<Order>();
+        unsafe { ptr::write(slot, order) };
+        self.used += 1;
+        NonNull::new(slot)
+    }
+
+    /// Hands every slot back at once. Pointers into the arena dangle after.
+    pub(crate) fn reset(&mut self) {
+        self.used = 0;
+    }
+
+    pub(crate) fn has_room(&self) -> bool {
+        self.used < self.len
+    }
+
+    pub(crate) fn used_bytes(&self) -> usize {
+        self.used * mem::size_of::<Order>()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.len * mem::size_of::<Order>()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        let block = ptr::slice_from_raw_parts_mut(self.slots.as_ptr(), self.len);
+        // SAFETY: `slots` and `len` came from `Box::into_raw` in `new`, and
+        // `MaybeUninit` slots are dropped without reading them.
+        drop(unsafe { Box::from_raw(block) });
+    }
+}
@@ -1,5 +1,7 @@
-use std::fmt;
-use std::ptr::NonNull;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ptr::NonNull;
+use core::str::FromStr;
 
 /// Represents a specific side of the Order Book.
 ///
@@ -23,6 +25,22 @@ impl Side {
     }
 }
 
+/// How a resting order reacts when an incoming order would trade against it.
+///
+/// Stored in the order's padding bytes, so it costs no extra space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RestMode {
+    /// Trade normally as a Maker.
+    #[default]
+    Normal = 0,
+    /// Never trade as a Maker: the order behaves like IOC from the moment it
+    /// rests. When a taker's matching walk reaches it (the opposite side's
+    /// aggressive price now touches or crosses it), it is pulled from the book
+    /// instead of filled, and matching continues with the next maker.
+    CancelIfCrossed = 1,
+}
+
 /// A strongly-typed wrapper around `u64` to represent price.
 ///
 /// **Zero-Cost Abstraction:**
@@ -30,27 +48,31 @@ impl Side {
 /// memory layout and ABI as a raw `u64`. This gives us compile-time
 /// type safety (preventing Price + Quantity bugs) without paying a
 /// performance penalty.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Price(pub u64);
 
 impl fmt::Display for Price {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Display as fixed-point (5 decimal places)
-        let s = format!("{:010}", self.0);
-        let len = s.len();
-        if len > 5 {
-            write!(f, "{}.{}", &s[0..len - 5], &s[len - 5..])
-        } else {
-            write!(f, "0.{}", &s)
-        }
+        // Display as fixed-point (5 decimal places, integer part padded to 5)
+        write!(f, "{:05}.{:05}", self.0 / 100_000, self.0 % 100_000)
+    }
+}
+
+impl FromStr for Price {
+    type Err = ParseDecimalError;
+
+    /// Parses the fixed-point form written by `Display` (up to 5 decimals),
+    /// e.g. `"50000.12345"` or `"50000"` (= `Price(5_000_000_000)`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_fixed(s, 5).map(Price)
     }
 }
 
 /// A strongly-typed wrapper around `u64` for quantity/size.
 ///
 /// Uses `#[repr(transparent)]` to ensure identical layout to `u64`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Quantity(pub u64);
 
@@ -63,17 +85,77 @@ impl Quantity {
 
 impl fmt::Display for Quantity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Display as fixed-point (3 decimal places for volume)
-        let s = format!("{:06}", self.0);
-        let len = s.len();
-        if len > 3 {
-            write!(f, "{}.{}", &s[0..len - 3], &s[len - 3..])
-        } else {
-            write!(f, "0.{}", &s)
+        // Display as fixed-point (3 decimal places for volume, integer part padded to 3)
+        write!(f, "{:03}.{:03}", self.0 / 1_000, self.0 % 1_000)
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = ParseDecimalError;
+
+    /// Parses the fixed-point form written by `Display` (up to 3 decimals).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_fixed(s, 3).map(Quantity)
+    }
+}
+
+/// Why a decimal string could not be parsed into a `Price` or `Quantity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    /// No digits at all.
+    Empty,
+    /// A character other than ASCII digits and a single `.`.
+    InvalidDigit,
+    /// More decimal places than the type's fixed-point scale.
+    TooManyDecimals,
+    /// The scaled value does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty decimal string"),
+            Self::InvalidDigit => write!(f, "invalid character in decimal string"),
+            Self::TooManyDecimals => write!(f, "too many decimal places"),
+            Self::Overflow => write!(f, "decimal value out of range"),
         }
     }
 }
 
+impl core::error::Error for ParseDecimalError {}
+
+/// Parses `int[.frac]` into an integer scaled by `10^decimals`.
+///
+/// Integer arithmetic only (no `f64`), so every value `Display` can produce
+/// round-trips exactly. Leading zeros (as `Display` pads) are accepted.
+fn parse_fixed(s: &str, decimals: u32) -> Result<u64, ParseDecimalError> {
+    let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return Err(ParseDecimalError::Empty);
+    }
+    if frac_part.len() > decimals as usize {
+        return Err(ParseDecimalError::TooManyDecimals);
+    }
+
+    let mut value: u64 = 0;
+    for byte in int_part.bytes().chain(frac_part.bytes()) {
+        if !byte.is_ascii_digit() {
+            return Err(ParseDecimalError::InvalidDigit);
+        }
+        value = value
+            .checked_mul(10)
+            .and_then(|v| v.checked_add((byte - b'0') as u64))
+            .ok_or(ParseDecimalError::Overflow)?;
+    }
+
+    // Scale up for any decimal places that were omitted.
+    let missing = decimals - frac_part.len() as u32;
+    value
+        .checked_mul(10u64.pow(missing))
+        .ok_or(ParseDecimalError::Overflow)
+}
+
 /// A unique identifier for an Order.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -103,22 +185,52 @@ pub type OrderPtr = NonNull<Order>;
 /// The Order Node stored in the Arena.
 ///
 /// **Cache Line Analysis:**
-/// - id (8) + price (8) + qty (8) + next (8) + prev (8) + side (1) = 41 bytes.
-/// - Alignment padding (7 bytes) brings total size to 48 bytes.
-/// - This fits comfortably within a standard 64-byte cache line.
+/// - id (8) + price (8) + qty (8) + display_qty (8) + total_qty (8)
+///   + next (8) + prev (8) + seq (8) + placed_at (8) + expiry (16) + side (1)
+///   + rest_mode (1) + pegged (1) + padding (1) + account (4) = 96 bytes.
+/// - `account` and `pegged` sit in what used to be alignment padding.
+/// - The iceberg, age and expiry fields push the node past a 64-byte cache line.
+///   The fields the matcher reads first (`id`, `price`, `qty`) still share
+///   the node's first line.
 #[derive(Debug, Clone)]
 #[repr(C)] // Guarantees C-compatible field ordering
 pub struct Order {
     pub id: OrderId,
     pub price: Price,
+    /// Quantity visible in the book and matchable right now. For an iceberg
+    /// this is the current slice; for a plain order, everything it holds.
     pub qty: Quantity,
+    /// Iceberg slice size: `qty` is refilled up to this from the hidden
+    /// reserve each time the slice is consumed. `0` for a plain order.
+    pub display_qty: Quantity,
+    /// Everything the order still holds, visible slice plus hidden reserve.
+    /// Equal to `qty` for a plain order.
+    pub total_qty: Quantity,
 
     // Intrusive Linked List Pointers
     pub next: Option<OrderPtr>,
     pub prev: Option<OrderPtr>,
 
+    /// Intake sequence number (see `OrderBook::next_seq`).
+    pub seq: u64,
+
+    /// When the order was placed, in the caller's clock (see
+    /// `OrderBook::set_clock`). Kept across amends; `0` if never stamped.
+    pub placed_at: u64,
+
+    /// Good-till-time: the order is cancelled by the first
+    /// `OrderBook::expire_orders` call with `now >= expiry`. `None` = GTC.
+    /// The unit is the caller's clock; the book only compares it.
+    pub expiry: Option<u64>,
+
     pub side: Side,
-    // +7 bytes padding inserted by compiler here
+    pub rest_mode: RestMode,
+    /// Placed by `OrderBook::place_pegged`: its price follows the opposite
+    /// best on each `OrderBook::reprice_pegs`.
+    pub pegged: bool,
+    // +1 byte padding inserted by compiler here
+    /// Owning account, for self-trade prevention (`0` = none).
+    pub account: u32,
 }
 
 impl Order {
@@ -128,8 +240,98 @@ impl Order {
             side,
             price,
             qty,
+            display_qty: Quantity(0),
+            total_qty: qty,
             next: None,
             prev: None,
+            seq: 0,
+            placed_at: 0,
+            expiry: None,
+            rest_mode: RestMode::Normal,
+            pegged: false,
+            account: 0,
+        }
+    }
+
+    /// `true` if part of the order is hidden behind a display slice.
+    pub fn is_iceberg(&self) -> bool {
+        self.display_qty.0 > 0
+    }
+
+    /// Quantity held back from the book, not yet in the visible slice.
+    pub fn hidden_qty(&self) -> Quantity {
+        self.total_qty.saturating_sub(self.qty)
+    }
+
+    /// The visible slice for `total` remaining: capped at `display_qty` for
+    /// an iceberg, all of it for a plain order.
+    pub fn slice_of(&self, total: Quantity) -> Quantity {
+        if self.is_iceberg() {
+            Quantity(total.0.min(self.display_qty.0))
+        } else {
+            total
+        }
+    }
+}
+
+/// An owned, pointer-free copy of a resting order's data.
+///
+/// Unlike `&Order`, a view is not tied to the book's borrow and carries no
+/// intrusive links, so it can be stored, sent elsewhere, or outlive the slot
+/// it was read from (which may since have been recycled). It holds every
+/// field but the links and the peg flag, enough to re-place the order
+/// elsewhere (see `OrderRequest::from`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderView {
+    pub id: OrderId,
+    pub side: Side,
+    pub price: Price,
+    /// Visible quantity (the current slice, for an iceberg).
+    pub qty: Quantity,
+    /// Iceberg slice size, `0` for a plain order.
+    pub display_qty: Quantity,
+    /// Visible slice plus hidden reserve.
+    pub total_qty: Quantity,
+    pub seq: u64,
+    pub placed_at: u64,
+    pub expiry: Option<u64>,
+    pub rest_mode: RestMode,
+    pub account: u32,
+}
+
+impl From<&Order> for OrderView {
+    fn from(order: &Order) -> Self {
+        Self {
+            id: order.id,
+            side: order.side,
+            price: order.price,
+            qty: order.qty,
+            display_qty: order.display_qty,
+            total_qty: order.total_qty,
+            seq: order.seq,
+            placed_at: order.placed_at,
+            expiry: order.expiry,
+            rest_mode: order.rest_mode,
+            account: order.account,
         }
     }
 }
+
+/// The book's queue-priority order: `Less` means `a` is matched before `b`.
+///
+/// Compares, in turn:
+/// 1. Price, side-aware: higher first for bids, lower first for asks.
+/// 2. `seq`, lower (earlier) first.
+/// 3. `OrderId`, lower first, so the order is total even for equal `seq`s.
+///
+/// This is the exact key `OrderBook` links resting orders by, so sorting a
+/// side's views with it reproduces the book's own order. Orders on different
+/// sides are not in one queue; they compare bids before asks.
+pub fn compare_priority(a: &OrderView, b: &OrderView) -> Ordering {
+    let price = match (a.side, b.side) {
+        (Side::Buy, Side::Buy) => b.price.cmp(&a.price),
+        (Side::Sell, Side::Sell) => a.price.cmp(&b.price),
+        (a_side, b_side) => return (a_side as u8).cmp(&(b_side as u8)),
+    };
+    price.then(a.seq.cmp(&b.seq)).then(a.id.0.cmp(&b.id.0))
+}
@@ -1,4 +1,5 @@
+pub(crate) mod arena;
 pub mod layout;
 
 // Re-export core types for easier access
-pub use layout::{Order, OrderId, Price, Quantity, Side};
+pub use layout::{Order, OrderId, OrderView, Price, Quantity, RestMode, Side, compare_priority};
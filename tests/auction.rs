@@ -0,0 +1,55 @@
+#![cfg(feature = "std")]
+
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::storage::layout::{Price, Quantity, Side};
+
+/// Restores resting orders as given, crossed or not, through the CSV loader
+/// (continuous placement would match them instead).
+fn restored_book(orders: &[(u64, Side, u64, u64)]) -> OrderBook {
+    let mut csv = String::from("id,side,price,qty,seq\n");
+    for (seq, &(id, side, price, qty)) in orders.iter().enumerate() {
+        let side = match side {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        };
+        csv += &format!("{id},{side},{},{},{seq}\n", Price(price), Quantity(qty));
+    }
+    OrderBook::from_csv("TEST", 32, &mut csv.as_bytes()).unwrap()
+}
+
+#[test]
+fn symmetric_book_uncrosses_at_the_middle() {
+    let mut book = restored_book(&[
+        (1, Side::Buy, 102, 10),
+        (2, Side::Buy, 101, 10),
+        (3, Side::Buy, 100, 10),
+        (4, Side::Buy, 97, 5),
+        (5, Side::Sell, 98, 10),
+        (6, Side::Sell, 99, 10),
+        (7, Side::Sell, 100, 10),
+        (8, Side::Sell, 103, 5),
+    ]);
+    assert!(book.is_crossed());
+
+    // Demand meets supply at 30 lots only at 100.
+    let (price, trades) = book.uncross();
+    assert_eq!(price, Some(Price(100)));
+    assert!(trades.iter().all(|t| t.price == Price(100)));
+    let matched: u64 = trades.iter().map(|t| t.quantity.0).sum();
+    assert_eq!(matched, 30);
+
+    // Only the orders outside the crossed region are left.
+    assert!(!book.is_crossed());
+    assert_eq!(book.best_prices(), (Some(Price(97)), Some(Price(103))));
+    assert_eq!(book.active_orders(), 2);
+    assert_eq!(book.validate(), Ok(()));
+}
+
+#[test]
+fn uncrossed_book_is_left_alone() {
+    let mut book = restored_book(&[(1, Side::Buy, 99, 10), (2, Side::Sell, 100, 10)]);
+    let (price, trades) = book.uncross();
+    assert_eq!(price, None);
+    assert!(trades.is_empty());
+    assert_eq!(book.active_orders(), 2);
+}
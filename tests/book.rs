@@ -0,0 +1,353 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, Side};
+
+/// Restores a book whose best bid sits through its best ask. The matcher
+/// never produces this, but a CSV dump stitched from two books does.
+#[cfg(feature = "std")]
+fn crossed_book() -> OrderBook {
+    let mut bids = OrderBook::new("TEST", 16);
+    bids.place_limit_order(1, Side::Buy, 105, 10).unwrap();
+    let mut asks = OrderBook::new("TEST", 16);
+    asks.place_limit_order(2, Side::Sell, 100, 10).unwrap();
+
+    let mut csv = Vec::new();
+    bids.to_csv(&mut csv).unwrap();
+    let mut ask_rows = Vec::new();
+    asks.to_csv(&mut ask_rows).unwrap();
+    let header_len = ask_rows.iter().position(|&b| b == b'\n').unwrap() + 1;
+    csv.extend_from_slice(&ask_rows[header_len..]);
+
+    OrderBook::from_csv("TEST", 16, &mut csv.as_slice()).unwrap()
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn spread_of_crossed_book_is_none() {
+    let book = crossed_book();
+    assert!(book.is_crossed());
+    assert_eq!(book.spread(), None);
+    // Display goes through `spread` and must not panic.
+    assert!(book.to_string().contains("---"));
+}
+
+#[test]
+fn spread_is_ask_minus_bid() {
+    let mut book = OrderBook::new("TEST", 16);
+    assert_eq!(book.spread(), None);
+    book.place_limit_order(1, Side::Sell, 100, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 97, 10).unwrap();
+    assert_eq!(book.spread(), Some(Price(3)));
+}
+
+#[test]
+fn mid_price_rounds_down_to_a_raw_price_unit() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.set_tick_size(5);
+    assert_eq!(book.mid_price(), None);
+    book.place_limit_order(1, Side::Sell, 105, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 90, 10).unwrap();
+    // (90 + 105) / 2 = 97.5: rounded down, not snapped to the 5-tick grid.
+    assert_eq!(book.mid_price(), Some(Price(97)));
+}
+
+#[test]
+fn arena_stats_track_the_lifetime_peak() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.prewarm();
+    assert_eq!(book.arena_stats().allocated_slots, 0);
+
+    for id in 0..4 {
+        book.place_limit_order(id, Side::Buy, 100 - id, 10).unwrap();
+    }
+    for id in 0..4 {
+        book.cancel_order(id).unwrap();
+    }
+    for id in 4..6 {
+        book.place_limit_order(id, Side::Buy, 100, 10).unwrap();
+    }
+    let stats = book.arena_stats();
+    assert_eq!(stats.allocated_slots, 4);
+    assert_eq!(stats.live_slots, 2);
+    assert_eq!(stats.reuse_ratio, 2.0 / 6.0);
+
+    // `clear` hands the memory back but not the history.
+    book.clear();
+    book.place_limit_order(6, Side::Sell, 110, 10).unwrap();
+    let stats = book.arena_stats();
+    assert_eq!(stats.allocated_slots, 4);
+    assert_eq!(stats.live_slots, 1);
+    assert_eq!(stats.reuse_ratio, 3.0 / 7.0);
+}
+
+#[test]
+fn taken_order_can_be_replaced_elsewhere() {
+    use zero_alloc_lob::engine::request::OrderRequest;
+
+    let mut book = OrderBook::new("TEST", 16);
+    book.place(OrderRequest {
+        account: 7,
+        timestamp: 42,
+        expiry: Some(1_000),
+        display_qty: Quantity(5),
+        ..OrderRequest::limit(1, Side::Sell, 100, 20)
+    })
+    .unwrap();
+    // Eat into the first slice so `qty` and `total_qty` differ.
+    book.place_limit_order(2, Side::Buy, 100, 3).unwrap();
+
+    let view = book.cancel_and_take(1).unwrap();
+    assert_eq!(book.active_orders(), 0);
+    assert_eq!(view.id, OrderId(1));
+    assert_eq!(view.qty, Quantity(2));
+    assert_eq!(view.display_qty, Quantity(5));
+    assert_eq!(view.total_qty, Quantity(17));
+    assert_eq!(view.account, 7);
+    assert_eq!(view.expiry, Some(1_000));
+    assert_eq!(view.placed_at, 42);
+
+    let mut other = OrderBook::new("TEST", 16);
+    other.place(OrderRequest::from(view)).unwrap();
+    let rebuilt = other.iter_all().next().unwrap();
+    assert_eq!(rebuilt.qty, Quantity(5));
+    assert_eq!(
+        (
+            rebuilt.total_qty,
+            rebuilt.account,
+            rebuilt.expiry,
+            rebuilt.placed_at
+        ),
+        (view.total_qty, view.account, view.expiry, view.placed_at)
+    );
+    assert_eq!(rebuilt.rest_mode, view.rest_mode);
+}
+
+#[test]
+fn volume_at_price_sums_one_level() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 3).unwrap();
+    book.place_limit_order(2, Side::Sell, 100, 5).unwrap();
+    book.place_limit_order(3, Side::Sell, 100, 7).unwrap();
+    book.place_limit_order(4, Side::Sell, 101, 50).unwrap();
+    book.place_limit_order(5, Side::Buy, 99, 50).unwrap();
+
+    assert_eq!(book.volume_at_price(Side::Sell, 100), Quantity(15));
+    assert_eq!(book.volume_at_price(Side::Sell, 102), Quantity(0));
+    assert_eq!(book.volume_at_price(Side::Buy, 100), Quantity(0));
+}
+
+#[test]
+fn volume_at_price_saturates() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, u64::MAX)
+        .unwrap();
+    book.place_limit_order(2, Side::Sell, 100, 1).unwrap();
+    assert_eq!(book.volume_at_price(Side::Sell, 100), Quantity(u64::MAX));
+}
+
+#[test]
+fn side_totals_count_and_sum_each_side() {
+    let mut book = OrderBook::new("TEST", 16);
+    assert_eq!(book.side_totals(Side::Buy), (0, Quantity(0)));
+    book.place_limit_order(1, Side::Buy, 99, 3).unwrap();
+    book.place_limit_order(2, Side::Buy, 98, 4).unwrap();
+    book.place_limit_order(3, Side::Sell, 101, 10).unwrap();
+
+    assert_eq!(book.side_totals(Side::Buy), (2, Quantity(7)));
+    assert_eq!(book.side_totals(Side::Sell), (1, Quantity(10)));
+    assert_eq!(book.total_volume(Side::Buy), Quantity(7));
+    assert_eq!(book.total_volume(Side::Sell), Quantity(10));
+
+    book.place_limit_order(4, Side::Sell, 102, u64::MAX)
+        .unwrap();
+    assert_eq!(book.side_totals(Side::Sell), (2, Quantity(u64::MAX)));
+    assert_eq!(book.total_volume(Side::Sell), Quantity(u64::MAX));
+}
+
+#[test]
+fn batch_carries_on_past_a_full_arena() {
+    use zero_alloc_lob::engine::error::OrderBookError;
+
+    let mut book = OrderBook::new("TEST", 2);
+    let results = book.place_batch(&[
+        (1, Side::Buy, 99, 5),
+        (2, Side::Sell, 101, 5),
+        // No slot left for a third resting order.
+        (3, Side::Buy, 98, 5),
+        // Fills order 1 completely, so it needs no slot and frees one.
+        (4, Side::Sell, 99, 5),
+        (5, Side::Buy, 98, 5),
+    ]);
+
+    assert_eq!(results.len(), 5);
+    assert!(results[0].as_ref().unwrap().0.is_some());
+    assert!(results[1].as_ref().unwrap().0.is_some());
+    assert_eq!(results[2].as_ref().unwrap_err(), &OrderBookError::ArenaFull);
+    assert_eq!(results[3].as_ref().unwrap().1.len(), 1);
+    assert!(results[4].as_ref().unwrap().0.is_some());
+
+    assert_eq!(book.best_prices(), (Some(Price(98)), Some(Price(101))));
+    assert_eq!(book.active_orders(), 2);
+    assert_eq!(book.queue_position(3), None);
+}
+
+#[test]
+fn queue_position_counts_orders_ahead_at_the_same_price() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(9, Side::Sell, 100, 5).unwrap();
+    for id in 1..=3 {
+        book.place_limit_order(id, Side::Sell, 101, 5).unwrap();
+    }
+
+    // Order 9 at the better price is not counted.
+    assert_eq!(book.queue_position(1), Some(0));
+    assert_eq!(book.queue_position(2), Some(1));
+    assert_eq!(book.queue_position(3), Some(2));
+    assert_eq!(book.queue_position(9), Some(0));
+
+    book.cancel_order(1).unwrap();
+    assert_eq!(book.queue_position(2), Some(0));
+    assert_eq!(book.queue_position(3), Some(1));
+    assert_eq!(book.queue_position(1), None);
+}
+
+#[test]
+fn placing_after_clear_reuses_the_arena() {
+    let mut book = OrderBook::new("TEST", 4);
+    let capacity = book.capacity_bytes();
+    for round in 0..3 {
+        for id in 0..4 {
+            book.place_limit_order(id, Side::Buy, 100 - id, 10).unwrap();
+        }
+        assert_eq!(book.used_bytes(), capacity, "round {round}");
+        book.clear();
+        assert_eq!(book.used_bytes(), 0);
+        assert_eq!(book.active_orders(), 0);
+    }
+    assert_eq!(book.capacity_bytes(), capacity);
+}
+
+#[test]
+fn clone_is_independent_of_the_original() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 10).unwrap();
+    book.place_limit_order(3, Side::Sell, 102, 10).unwrap();
+    let depth = book.depth_snapshot(10);
+
+    let mut fork = book.clone();
+    assert_eq!(fork.depth_snapshot(10), depth);
+    // Sweep, rest, amend and cancel in the fork only.
+    fork.place_limit_order(4, Side::Buy, 101, 15).unwrap();
+    fork.place_limit_order(5, Side::Buy, 98, 10).unwrap();
+    fork.modify_order(1, 97, 5).unwrap();
+    fork.cancel_order(3).unwrap();
+    assert_ne!(fork.depth_snapshot(10), depth);
+
+    assert_eq!(book.depth_snapshot(10), depth);
+    assert_eq!(book.active_orders(), 3);
+    // The original's orders still live in its own arena.
+    book.cancel_order(3).unwrap();
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(10));
+}
+
+#[test]
+fn cancel_returns_the_unfilled_remainder() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 100, 3).unwrap();
+    book.place_limit_order(3, Side::Buy, 100, 4).unwrap();
+
+    assert_eq!(book.cancel_order(1), Ok((OrderId(1), Quantity(3))));
+    assert_eq!(book.volume_at_price(Side::Sell, 100), Quantity(0));
+    // The slot is recycled, but the answer was read before that.
+    book.place_limit_order(4, Side::Sell, 105, 8).unwrap();
+    assert_eq!(book.cancel_order(4), Ok((OrderId(4), Quantity(8))));
+}
+
+#[test]
+fn cancel_where_keeps_the_survivors_linked() {
+    let mut book = OrderBook::new("TEST", 32);
+    // Asks 100..=109 interleaved with bids, two asks at 105.
+    for i in 0..10 {
+        book.place_limit_order(i, Side::Sell, 100 + i, 10).unwrap();
+        book.place_limit_order(100 + i, Side::Buy, 90 - i, 10)
+            .unwrap();
+    }
+    book.place_limit_order(50, Side::Sell, 105, 10).unwrap();
+
+    let cancelled = book.cancel_where(|o| o.side == Side::Sell && o.price.0 > 104);
+    let expected: Vec<OrderId> = [5, 50, 6, 7, 8, 9].map(OrderId).into();
+    assert_eq!(cancelled, expected);
+
+    let asks: Vec<u64> = book.iter_side(Side::Sell).map(|o| o.price.0).collect();
+    assert_eq!(asks, [100, 101, 102, 103, 104]);
+    assert_eq!(book.side_totals(Side::Buy), (10, Quantity(100)));
+    assert_eq!(book.active_orders(), 15);
+    assert_eq!(book.free_slots(), 6);
+    #[cfg(feature = "std")]
+    assert_eq!(book.validate(), Ok(()));
+
+    // The cancelled ids are gone from the index; the survivors still trade.
+    assert!(book.cancel_order(50).is_err());
+    book.place_limit_order(200, Side::Sell, 105, 1).unwrap();
+    let (_, trades) = book.place_limit_order(201, Side::Buy, 105, 51).unwrap();
+    assert_eq!(trades.len(), 6);
+    assert_eq!(book.best_ask_price(), None);
+}
+
+#[test]
+fn order_age_is_measured_from_placement() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use zero_alloc_lob::engine::request::OrderRequest;
+
+    // Caller-supplied timestamp.
+    let mut book = OrderBook::new("TEST", 16);
+    book.place(OrderRequest {
+        timestamp: 100,
+        ..OrderRequest::limit(1, Side::Buy, 99, 10)
+    })
+    .unwrap();
+    assert_eq!(book.order_age(1, 250), Some(150));
+    assert_eq!(book.order_age(1, 50), Some(0));
+    assert_eq!(book.order_age(2, 250), None);
+    // An amend that loses priority keeps the original stamp.
+    book.modify_order(1, 98, 10).unwrap();
+    assert_eq!(book.order_age(1, 250), Some(150));
+
+    // A registered clock wins over the request's timestamp.
+    let now = Arc::new(AtomicU64::new(100));
+    let clock = Arc::clone(&now);
+    let mut book = OrderBook::new("TEST", 16);
+    book.set_clock(move || clock.load(Ordering::Relaxed));
+    book.place(OrderRequest {
+        timestamp: 7,
+        ..OrderRequest::limit(1, Side::Buy, 99, 10)
+    })
+    .unwrap();
+    now.store(250, Ordering::Relaxed);
+    assert_eq!(book.order_age(1, 250), Some(150));
+}
+
+#[test]
+fn book_can_move_to_another_thread() {
+    fn assert_send<T: Send>() {}
+    assert_send::<OrderBook>();
+
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 101, 10).unwrap();
+    book.on_trade(|_| {});
+
+    let mut book = std::thread::spawn(move || {
+        // The arena moved with the book: its orders are still reachable.
+        let (_, trades) = book.place_limit_order(2, Side::Buy, 101, 4).unwrap();
+        assert_eq!(trades[0].maker_id, OrderId(1));
+        book.place_limit_order(3, Side::Buy, 99, 5).unwrap();
+        book
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(6));
+    assert_eq!(book.cancel_order(3), Ok((OrderId(3), Quantity(5))));
+}
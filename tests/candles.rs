@@ -0,0 +1,72 @@
+use zero_alloc_lob::engine::candles::{Candle, CandleAggregator};
+use zero_alloc_lob::engine::matcher::Trade;
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, Side};
+
+fn trade(price: u64, qty: u64) -> Trade {
+    Trade {
+        maker_id: OrderId(1),
+        taker_id: OrderId(2),
+        price: Price(price),
+        quantity: Quantity(qty),
+        maker_side: Side::Sell,
+        maker_leaves_qty: Quantity(0),
+        seq: 0,
+        maker_seq: 0,
+        taker_recv_ts: None,
+        maker_fee: 0,
+        taker_fee: 0,
+    }
+}
+
+fn candle(start: u64, ohlc: [u64; 4], volume: u64) -> Candle {
+    Candle {
+        start,
+        open: Price(ohlc[0]),
+        high: Price(ohlc[1]),
+        low: Price(ohlc[2]),
+        close: Price(ohlc[3]),
+        volume: Quantity(volume),
+    }
+}
+
+#[test]
+fn known_trade_sequence_builds_known_bars() {
+    let mut bars = CandleAggregator::new(60);
+    let mut closed = Vec::new();
+    // (time, price, qty)
+    let tape = [
+        (5, 100, 1),
+        (20, 104, 2),
+        (30, 98, 3),
+        (59, 101, 4),
+        (60, 101, 5),
+        (119, 99, 1),
+        // 120..180 is quiet.
+        (200, 110, 7),
+    ];
+    for (now, price, qty) in tape {
+        closed.extend(bars.roll(now));
+        bars.push(&trade(price, qty));
+    }
+    closed.extend(bars.roll(240));
+
+    assert_eq!(
+        closed,
+        [
+            candle(0, [100, 104, 98, 101], 10),
+            candle(60, [101, 101, 99, 99], 6),
+            candle(180, [110, 110, 110, 110], 7),
+        ]
+    );
+    assert_eq!(bars.current(), None);
+}
+
+#[test]
+fn late_roll_is_ignored() {
+    let mut bars = CandleAggregator::new(10);
+    assert_eq!(bars.roll(25), None);
+    bars.push(&trade(100, 1));
+    assert_eq!(bars.roll(3), None);
+    bars.push(&trade(90, 1));
+    assert_eq!(bars.roll(30), Some(candle(20, [100, 100, 90, 90], 2)));
+}
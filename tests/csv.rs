@@ -0,0 +1,64 @@
+#![cfg(feature = "std")]
+
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::storage::layout::Side;
+
+fn dump(book: &OrderBook) -> Vec<u8> {
+    let mut csv = Vec::new();
+    book.to_csv(&mut csv).unwrap();
+    csv
+}
+
+#[test]
+fn csv_round_trip_reproduces_depth_and_priority() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99_50000, 1_500)
+        .unwrap();
+    book.place_limit_order(2, Side::Buy, 99_50000, 250).unwrap();
+    book.place_limit_order(3, Side::Buy, 98_00000, 1_000)
+        .unwrap();
+    book.place_limit_order(4, Side::Sell, 100_25000, 2_000)
+        .unwrap();
+    book.place_limit_order(5, Side::Sell, 101_00000, 1).unwrap();
+    // Move order 1 behind order 2.
+    book.modify_order(1, 99_00000, 1_500).unwrap();
+    book.modify_order(1, 99_50000, 1_500).unwrap();
+
+    let csv = dump(&book);
+    let mut restored = OrderBook::from_csv("TEST", 16, &mut csv.as_slice()).unwrap();
+
+    assert_eq!(restored.depth_snapshot(10), book.depth_snapshot(10));
+    assert_eq!(restored.queue_position(2), Some(0));
+    assert_eq!(restored.queue_position(1), Some(1));
+    assert_eq!(dump(&restored), csv);
+    // New orders queue behind the restored ones.
+    restored
+        .place_limit_order(6, Side::Buy, 99_50000, 1)
+        .unwrap();
+    assert_eq!(restored.queue_position(6), Some(2));
+}
+
+#[test]
+fn malformed_row_reports_its_line_number() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 98, 10).unwrap();
+    let mut csv = String::from_utf8(dump(&book)).unwrap();
+    // Header, two rows, a blank line, then the bad row on line 5.
+    csv.push_str("\n3,sideways,1.00000,1.000,7\n");
+
+    let err = OrderBook::from_csv("TEST", 16, &mut csv.as_bytes()).err();
+    let err = err.expect("the bad row is rejected");
+    assert_eq!(err, OrderBookError::InvalidCsvRow(5));
+    assert_eq!(err.to_string(), "Malformed order book CSV at line 5");
+}
+
+#[test]
+fn missing_header_is_line_one() {
+    let csv = "1,buy,1.00000,1.000,0\n";
+    assert_eq!(
+        OrderBook::from_csv("TEST", 16, &mut csv.as_bytes()).err(),
+        Some(OrderBookError::InvalidCsvRow(1))
+    );
+}
@@ -0,0 +1,152 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::storage::layout::{Price, Quantity, Side};
+
+#[test]
+fn imbalance_of_a_lopsided_book() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99, 20).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(3, Side::Buy, 98, 10).unwrap();
+    book.place_limit_order(4, Side::Buy, 90, 100).unwrap();
+    book.place_limit_order(5, Side::Sell, 101, 10).unwrap();
+
+    // Top two levels: 40 bid against 10 ask.
+    assert_eq!(book.imbalance(2), Some(0.6));
+    // The deep bid counts once it is in range; the ask side has one level.
+    assert_eq!(book.imbalance(3), Some(130.0 / 150.0));
+    assert_eq!(book.imbalance(0), None);
+
+    book.cancel_order(5).unwrap();
+    assert_eq!(book.imbalance(2), None);
+}
+
+#[test]
+fn notional_of_known_levels() {
+    let mut book = OrderBook::new("TEST", 16);
+    assert_eq!(book.total_notional(Side::Sell), 0);
+    assert_eq!(book.notional_at_price(Side::Sell, 100), 0);
+
+    book.place_limit_order(1, Side::Sell, 100, 3).unwrap();
+    book.place_limit_order(2, Side::Sell, 100, 4).unwrap();
+    book.place_limit_order(3, Side::Sell, 110, 10).unwrap();
+    book.place_limit_order(4, Side::Buy, 90, 2).unwrap();
+
+    assert_eq!(book.notional_at_price(Side::Sell, 100), 700);
+    assert_eq!(book.notional_at_price(Side::Sell, 105), 0);
+    assert_eq!(book.total_notional(Side::Sell), 700 + 1_100);
+    assert_eq!(book.total_notional(Side::Buy), 180);
+    book.cancel_order(4).unwrap();
+    assert_eq!(book.total_notional(Side::Buy), 0);
+}
+
+#[test]
+fn notional_does_not_overflow() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, u64::MAX, u64::MAX)
+        .unwrap();
+    let expected = u64::MAX as u128 * u64::MAX as u128;
+    assert_eq!(book.notional_at_price(Side::Sell, u64::MAX), expected);
+    assert_eq!(book.total_notional(Side::Sell), expected);
+}
+
+#[test]
+fn top_levels_with_fewer_levels_than_slots() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 5).unwrap();
+    book.place_limit_order(3, Side::Buy, 97, 7).unwrap();
+    book.place_limit_order(4, Side::Buy, 95, u64::MAX).unwrap();
+    book.place_limit_order(5, Side::Buy, 95, 1).unwrap();
+
+    let (ladder, count) = book.top_levels::<5>(Side::Buy);
+    assert_eq!(count, 3);
+    assert_eq!(
+        ladder,
+        [
+            (Price(99), Quantity(15)),
+            (Price(97), Quantity(7)),
+            (Price(95), Quantity(u64::MAX)),
+            (Price(0), Quantity(0)),
+            (Price(0), Quantity(0)),
+        ]
+    );
+    assert_eq!(
+        book.top_levels::<5>(Side::Sell),
+        ([(Price(0), Quantity(0)); 5], 0)
+    );
+}
+
+#[test]
+fn snapshot_is_unaffected_by_later_writes() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 10).unwrap();
+    let snapshot = book.snapshot();
+    let copy = snapshot.clone();
+
+    book.place_limit_order(3, Side::Buy, 101, 4).unwrap();
+    book.place_limit_order(4, Side::Buy, 100, 7).unwrap();
+    book.cancel_order(1).unwrap();
+    book.clear();
+    book.place_limit_order(5, Side::Sell, 120, 1).unwrap();
+
+    assert_eq!(snapshot, copy);
+    assert_eq!(snapshot.bids(), [(Price(99), Quantity(10))]);
+    assert_eq!(snapshot.asks(), [(Price(101), Quantity(10))]);
+    assert!(snapshot.version() < book.version());
+    // A reader thread sees the same thing.
+    let reader = std::thread::spawn(move || snapshot.best_ask_price());
+    assert_eq!(reader.join().unwrap(), Some(Price(101)));
+}
+
+#[test]
+fn level_count_with_shared_and_distinct_prices() {
+    let mut book = OrderBook::new("TEST", 16);
+    assert_eq!(book.level_count(Side::Buy), 0);
+
+    book.place_limit_order(1, Side::Buy, 99, 5).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 5).unwrap();
+    book.place_limit_order(3, Side::Buy, 98, 5).unwrap();
+    book.place_limit_order(4, Side::Sell, 101, 5).unwrap();
+    assert_eq!(book.level_count(Side::Buy), 2);
+    assert_eq!(book.level_count(Side::Sell), 1);
+
+    // A level only goes away with its last order.
+    book.cancel_order(1).unwrap();
+    assert_eq!(book.level_count(Side::Buy), 2);
+    book.cancel_order(2).unwrap();
+    assert_eq!(book.level_count(Side::Buy), 1);
+}
+
+#[test]
+fn depth_level_sizes_saturate() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99, u64::MAX).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 1).unwrap();
+    book.place_limit_order(3, Side::Buy, 98, 7).unwrap();
+
+    assert_eq!(
+        book.side_depth(Side::Buy, 5),
+        [(Price(99), Quantity(u64::MAX)), (Price(98), Quantity(7))]
+    );
+}
+
+#[test]
+fn nth_and_top_level_sizes_saturate() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 101, u64::MAX)
+        .unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 1).unwrap();
+    book.place_limit_order(3, Side::Sell, 102, u64::MAX)
+        .unwrap();
+    book.place_limit_order(4, Side::Sell, 102, 1).unwrap();
+
+    let full = (Price(101), Quantity(u64::MAX));
+    assert_eq!(book.nth_level(Side::Sell, 0), Some(full));
+    assert_eq!(
+        book.nth_level(Side::Sell, 1),
+        Some((Price(102), Quantity(u64::MAX)))
+    );
+    assert_eq!(book.nth_level(Side::Sell, 2), None);
+    assert_eq!(book.quote().ask, Some(full));
+}
@@ -0,0 +1,64 @@
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::engine::manager::MatchingEngine;
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, Side};
+
+#[test]
+fn orders_are_routed_to_their_own_symbol() {
+    let mut engine = MatchingEngine::new();
+    engine.add_symbol("BTC", 16);
+    engine.add_symbol("ETH", 16);
+
+    // The same id may rest on both books.
+    engine.place("BTC", 1, Side::Sell, 100, 10).unwrap();
+    engine.place("ETH", 1, Side::Sell, 200, 10).unwrap();
+
+    // A buy on ETH at BTC's ask price must not see BTC's order.
+    let (resting, trades) = engine.place("ETH", 2, Side::Buy, 100, 4).unwrap();
+    assert!(trades.is_empty());
+    assert!(resting.is_some());
+
+    // One that crosses ETH's own ask trades there, and only there.
+    let (_, trades) = engine.place("ETH", 3, Side::Buy, 200, 4).unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].maker_id, OrderId(1));
+    assert_eq!(trades[0].price, Price(200));
+
+    let btc = engine.book("BTC").unwrap();
+    assert_eq!(btc.active_orders(), 1);
+    assert_eq!(btc.volume_at_price(Side::Sell, 100), Quantity(10));
+    let eth = engine.book("ETH").unwrap();
+    assert_eq!(eth.best_prices(), (Some(Price(100)), Some(Price(200))));
+    assert_eq!(eth.volume_at_price(Side::Sell, 200), Quantity(6));
+
+    // Cancels route too.
+    assert_eq!(engine.cancel("BTC", 1), Ok((OrderId(1), Quantity(10))));
+    assert_eq!(engine.book("BTC").unwrap().active_orders(), 0);
+    assert_eq!(engine.book("ETH").unwrap().active_orders(), 2);
+}
+
+#[test]
+fn unknown_symbol_is_rejected() {
+    let mut engine = MatchingEngine::new();
+    engine.add_symbol("BTC", 16);
+    assert!(matches!(
+        engine.place("SOL", 1, Side::Buy, 100, 1),
+        Err(OrderBookError::UnknownSymbol)
+    ));
+    assert_eq!(engine.cancel("SOL", 1), Err(OrderBookError::UnknownSymbol));
+    assert!(engine.book("SOL").is_none());
+}
+
+#[test]
+fn sequence_numbers_are_global_across_symbols() {
+    let mut engine = MatchingEngine::new();
+    engine.add_symbol("BTC", 16);
+    engine.add_symbol("ETH", 16);
+    engine.place("BTC", 1, Side::Sell, 100, 10).unwrap();
+    engine.place("ETH", 1, Side::Sell, 200, 10).unwrap();
+    let (_, trades) = engine.place("BTC", 2, Side::Buy, 100, 1).unwrap();
+
+    // BTC's order 1 took seq 0, ETH's 1, the taker 2 and its trade 3.
+    assert_eq!(trades[0].maker_seq, 0);
+    assert_eq!(trades[0].seq, 3);
+    assert_eq!(engine.manager().next_seq(), 4);
+}
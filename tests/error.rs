@@ -0,0 +1,24 @@
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::storage::layout::OrderId;
+
+/// Pins every `OrdRejReason` code: these go out on the wire.
+#[test]
+fn fix_reject_codes_are_stable() {
+    let id = OrderId(1);
+    let code = OrderBookError::fix_reject_code;
+    assert_eq!(code(&OrderBookError::UnknownSymbol), 1);
+    assert_eq!(code(&OrderBookError::KillSwitchActive), 2);
+    assert_eq!(code(&OrderBookError::RateLimited), 3);
+    assert_eq!(code(&OrderBookError::OrderNotFound(id)), 5);
+    assert_eq!(code(&OrderBookError::DuplicateId(id)), 6);
+    assert_eq!(code(&OrderBookError::InvalidOrderType), 11);
+    assert_eq!(code(&OrderBookError::InvalidQuantity), 13);
+    assert_eq!(code(&OrderBookError::WouldIncreaseQty(id)), 13);
+    assert_eq!(code(&OrderBookError::ArenaFull), 99);
+    assert_eq!(code(&OrderBookError::TradeBufferFull), 99);
+    assert_eq!(code(&OrderBookError::WouldLosePriority(id)), 99);
+    assert_eq!(code(&OrderBookError::PostOnlyWouldCross(id)), 99);
+    assert_eq!(code(&OrderBookError::InvalidPrice), 99);
+    assert_eq!(code(&OrderBookError::InvalidSnapshot), 99);
+    assert_eq!(code(&OrderBookError::InvalidCsvRow(3)), 99);
+}
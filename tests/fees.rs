@@ -0,0 +1,56 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::matcher::FeeSchedule;
+use zero_alloc_lob::storage::layout::{Price, Quantity, Side};
+
+const SCHEDULE: FeeSchedule = FeeSchedule {
+    maker_bps: 1,
+    taker_bps: 5,
+};
+
+#[test]
+fn fees_for_a_known_fill() {
+    // 2.000 at 100.00000: notional 200, so 1 bp is 0.02 and 5 bp is 0.10.
+    assert_eq!(
+        SCHEDULE.fees(Price(10_000_000), Quantity(2_000)),
+        (2_000, 10_000)
+    );
+}
+
+#[test]
+fn fees_round_up_to_the_next_tick() {
+    // 0.001 at 0.00001 is far below one tick of fee, yet never free.
+    assert_eq!(SCHEDULE.fees(Price(1), Quantity(1)), (1, 1));
+    // 1 bp of 1.000 at 0.20001 is 2.0001 ticks: just past 2, so 3.
+    assert_eq!(
+        FeeSchedule {
+            maker_bps: 1,
+            taker_bps: 0,
+        }
+        .fees(Price(20_001), Quantity(1_000)),
+        (3, 0)
+    );
+}
+
+#[test]
+fn fees_saturate_instead_of_wrapping() {
+    let steep = FeeSchedule {
+        maker_bps: u32::MAX,
+        taker_bps: u32::MAX,
+    };
+    assert_eq!(
+        steep.fees(Price(u64::MAX), Quantity(u64::MAX)),
+        (u64::MAX, u64::MAX)
+    );
+}
+
+#[test]
+fn trades_carry_the_book_schedule() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.set_fee_schedule(SCHEDULE);
+    book.place_limit_order(1, Side::Sell, 10_000_000, 2_000)
+        .unwrap();
+    let (_, trades) = book
+        .place_limit_order(2, Side::Buy, 10_000_000, 2_000)
+        .unwrap();
+    assert_eq!((trades[0].maker_fee, trades[0].taker_fee), (2_000, 10_000));
+}
@@ -0,0 +1,70 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::matcher::{MatchMode, SelfTradePrevention};
+use zero_alloc_lob::engine::request::{OrderRequest, TimeInForce};
+use zero_alloc_lob::storage::layout::{Quantity, RestMode, Side};
+
+/// Small xorshift generator, so the randomized test needs no dependency and
+/// replays identically.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+#[test]
+fn book_is_never_left_crossed() {
+    let mut rng = Rng(0x853c_49e6_748f_ea9b);
+    for round in 0..100 {
+        let mut book = OrderBook::new("TEST", 64);
+        if rng.below(2) == 0 {
+            book.set_match_mode(MatchMode::ProRata);
+        }
+        if rng.below(2) == 0 {
+            book.set_self_trade_prevention(Some(SelfTradePrevention::CancelIncoming));
+        }
+
+        for step in 0..300 {
+            let id = rng.below(80);
+            let side = if rng.below(2) == 0 {
+                Side::Buy
+            } else {
+                Side::Sell
+            };
+            let price = 95 + rng.below(11);
+            let qty = rng.below(20) + 1;
+            // Every entry point that can move the touch. Rejections are fine.
+            let _ = match rng.below(6) {
+                0 => book.cancel_order(id).map(drop),
+                1 => book.modify_order(id, price, qty).map(drop),
+                2 => book.place_market_order(id, side, qty).map(drop),
+                3 => book
+                    .place(OrderRequest {
+                        account: rng.below(3) as u32,
+                        display_qty: Quantity(rng.below(qty + 1)),
+                        rest_mode: match rng.below(4) {
+                            0 => RestMode::CancelIfCrossed,
+                            _ => RestMode::Normal,
+                        },
+                        tif: match rng.below(4) {
+                            0 => TimeInForce::Ioc,
+                            _ => TimeInForce::Gtc,
+                        },
+                        ..OrderRequest::limit(id, side, price, qty)
+                    })
+                    .map(drop),
+                _ => book.place_limit_order(id, side, price, qty).map(drop),
+            };
+            assert!(!book.is_crossed(), "round {round}, step {step}");
+            if let (Some(bid), Some(ask)) = book.best_prices() {
+                assert!(bid < ask, "round {round}, step {step}");
+            }
+        }
+        #[cfg(feature = "std")]
+        assert_eq!(book.validate(), Ok(()), "round {round}");
+    }
+}
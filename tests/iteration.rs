@@ -0,0 +1,126 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::storage::layout::{OrderView, Side, compare_priority};
+
+/// Ten levels per side, every other price: asks 100, 102, .., 118 and bids
+/// 98, 96, .., 80. The 108 ask level holds two orders.
+fn ladder() -> OrderBook {
+    let mut book = OrderBook::new("TEST", 64);
+    for i in 0..10 {
+        book.place_limit_order(i, Side::Sell, 100 + 2 * i, 10)
+            .unwrap();
+        book.place_limit_order(100 + i, Side::Buy, 98 - 2 * i, 10)
+            .unwrap();
+    }
+    book.place_limit_order(50, Side::Sell, 108, 10).unwrap();
+    book
+}
+
+fn prices_from(book: &OrderBook, side: Side, price: u64) -> Vec<u64> {
+    book.iter_from(side, price).map(|o| o.price.0).collect()
+}
+
+#[test]
+fn iter_from_mid_book_starts_at_that_level() {
+    let book = ladder();
+    assert_eq!(
+        prices_from(&book, Side::Sell, 108),
+        [108, 108, 110, 112, 114, 116, 118]
+    );
+    let ids: Vec<_> = book
+        .iter_from(Side::Sell, 108)
+        .map(|o| o.id.0)
+        .take(2)
+        .collect();
+    assert_eq!(ids, [4, 50]);
+
+    assert_eq!(prices_from(&book, Side::Buy, 88), [88, 86, 84, 82, 80]);
+}
+
+#[test]
+fn iter_from_between_levels_starts_at_the_next_worse_one() {
+    let book = ladder();
+    assert_eq!(prices_from(&book, Side::Sell, 113), [114, 116, 118]);
+    assert_eq!(prices_from(&book, Side::Buy, 85), [84, 82, 80]);
+}
+
+#[test]
+fn iter_from_outside_the_ladder() {
+    let book = ladder();
+    // Better than the best: the whole side.
+    assert_eq!(prices_from(&book, Side::Sell, 1).len(), 11);
+    assert_eq!(prices_from(&book, Side::Buy, 1_000).len(), 10);
+    // Past the worst: nothing.
+    assert!(prices_from(&book, Side::Sell, 119).is_empty());
+    assert!(prices_from(&book, Side::Buy, 79).is_empty());
+}
+
+#[test]
+fn side_iterators_walk_away_from_the_touch() {
+    let book = ladder();
+    let asks: Vec<u64> = book.iter_side(Side::Sell).map(|o| o.price.0).collect();
+    let bids: Vec<u64> = book.iter_side(Side::Buy).map(|o| o.price.0).collect();
+
+    assert_eq!(
+        asks,
+        [100, 102, 104, 106, 108, 108, 110, 112, 114, 116, 118]
+    );
+    assert_eq!(bids, [98, 96, 94, 92, 90, 88, 86, 84, 82, 80]);
+    // Within a level, time priority.
+    let at_108: Vec<u64> = book
+        .iter_side(Side::Sell)
+        .filter(|o| o.price.0 == 108)
+        .map(|o| o.id.0)
+        .collect();
+    assert_eq!(at_108, [4, 50]);
+}
+
+/// Small xorshift generator, so the randomized test needs no dependency and
+/// replays identically.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+#[test]
+fn compare_priority_matches_the_book_order_on_random_books() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for round in 0..200 {
+        let mut book = OrderBook::new("TEST", 128);
+        for id in 0..100 {
+            let side = if rng.below(2) == 0 {
+                Side::Buy
+            } else {
+                Side::Sell
+            };
+            let price = match side {
+                Side::Buy => 90 + rng.below(10),
+                Side::Sell => 101 + rng.below(10),
+            };
+            book.place_limit_order(id, side, price, rng.below(20) + 1)
+                .unwrap();
+            // Churn: cancels, and amends that reduce in place, re-queue at a
+            // new level or cross and match again. Missing ids just fail.
+            let other = rng.below(id + 1);
+            let _ = match rng.below(4) {
+                0 => book.cancel_order(other).map(drop),
+                1 => book.modify_order(other, price, 1).map(drop),
+                _ => Ok(()),
+            };
+        }
+
+        for side in [Side::Buy, Side::Sell] {
+            let queue: Vec<OrderView> = book.iter_side(side).map(OrderView::from).collect();
+            let mut sorted = queue.clone();
+            sorted.reverse();
+            sorted.sort_by(compare_priority);
+            let ids = |views: &[OrderView]| views.iter().map(|v| v.id).collect::<Vec<_>>();
+            assert_eq!(ids(&sorted), ids(&queue), "round {round}");
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::engine::matcher::{
+    PricingPolicy, SelfTradePrevention, TakerConstraints, Trade, coalesce_trades,
+};
+use zero_alloc_lob::engine::request::{OrderRequest, TimeInForce};
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, RestMode, Side};
+
+fn fills(trades: &[Trade]) -> Vec<(OrderId, Price, Quantity)> {
+    trades
+        .iter()
+        .map(|t| (t.maker_id, t.price, t.quantity))
+        .collect()
+}
+
+fn min_fill(qty: u64) -> TakerConstraints {
+    TakerConstraints {
+        min_fill_per_maker: Some(Quantity(qty)),
+        ..TakerConstraints::default()
+    }
+}
+
+/// A small ask at the head of 100, a large one behind it, more at 101.
+fn small_ask_first() -> OrderBook {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 2).unwrap();
+    book.place_limit_order(2, Side::Sell, 100, 10).unwrap();
+    book.place_limit_order(3, Side::Sell, 101, 10).unwrap();
+    book
+}
+
+#[test]
+fn min_fill_skips_small_makers_at_the_same_price() {
+    let mut book = small_ask_first();
+    let (resting, trades) = book
+        .place_limit_order_with(10, Side::Buy, 101, 8, min_fill(5))
+        .unwrap();
+    assert_eq!(fills(&trades), [(OrderId(2), Price(100), Quantity(8))]);
+    assert!(resting.is_none());
+    // The skipped maker keeps its size and its place.
+    assert_eq!(book.queue_position(1), Some(0));
+    assert_eq!(book.volume_at_price(Side::Sell, 100), Quantity(4));
+}
+
+#[test]
+fn min_fill_never_trades_through_a_skipped_maker() {
+    let mut book = small_ask_first();
+    let (resting, trades) = book
+        .place_limit_order_with(10, Side::Buy, 101, 15, min_fill(5))
+        .unwrap();
+    // Order 3 at 101 would fill the rest, but order 1 at 100 was skipped.
+    assert_eq!(fills(&trades), [(OrderId(2), Price(100), Quantity(10))]);
+    // The remainder would cross order 1, so it is cancelled, not rested.
+    assert!(resting.is_none());
+    assert_eq!(book.best_bid_price(), None);
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(10));
+}
+
+#[test]
+fn min_fill_accepts_a_small_fill_that_completes_the_taker() {
+    let mut book = small_ask_first();
+    let (_, trades) = book
+        .place_limit_order_with(10, Side::Buy, 100, 2, min_fill(5))
+        .unwrap();
+    assert_eq!(fills(&trades), [(OrderId(1), Price(100), Quantity(2))]);
+}
+
+#[test]
+fn cancel_if_crossed_maker_is_pulled_when_the_walk_reaches_it() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 5).unwrap();
+    book.place(OrderRequest {
+        rest_mode: RestMode::CancelIfCrossed,
+        ..OrderRequest::limit(2, Side::Sell, 101, 5)
+    })
+    .unwrap();
+    book.place_limit_order(3, Side::Sell, 101, 5).unwrap();
+
+    // Only reaches 100: order 2 is never touched.
+    book.place_limit_order(10, Side::Buy, 101, 3).unwrap();
+    assert_eq!(book.drain_auto_cancelled().count(), 0);
+
+    // Clears 100, then reaches order 2 at 101 and pulls it unfilled.
+    let (_, trades) = book.place_limit_order(11, Side::Buy, 101, 6).unwrap();
+    assert_eq!(
+        fills(&trades),
+        [
+            (OrderId(1), Price(100), Quantity(2)),
+            (OrderId(3), Price(101), Quantity(4)),
+        ]
+    );
+    assert!(book.drain_auto_cancelled().eq([OrderId(2)]));
+    assert_eq!(book.queue_position(2), None);
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(1));
+}
+
+#[test]
+fn ioc_remainder_is_never_resting() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 4).unwrap();
+
+    let (resting, trades) = book
+        .place_limit_order_tif(10, Side::Buy, 100, 10, TimeInForce::Ioc)
+        .unwrap();
+    assert_eq!(fills(&trades), [(OrderId(1), Price(100), Quantity(4))]);
+    assert!(resting.is_none());
+    assert_eq!(
+        book.cancel_order(10).unwrap_err(),
+        OrderBookError::OrderNotFound(OrderId(10))
+    );
+    assert_eq!(book.active_orders(), 0);
+}
+
+#[test]
+fn self_trade_prevention_never_prints_a_same_account_trade() {
+    let policies = [
+        SelfTradePrevention::CancelResting,
+        SelfTradePrevention::CancelIncoming,
+        SelfTradePrevention::Decrement,
+    ];
+    for policy in policies {
+        let mut book = OrderBook::new("TEST", 16);
+        book.set_self_trade_prevention(Some(policy));
+        book.place(OrderRequest {
+            account: 7,
+            ..OrderRequest::limit(1, Side::Sell, 100, 5)
+        })
+        .unwrap();
+
+        let (_, trades) = book
+            .place(OrderRequest {
+                account: 7,
+                ..OrderRequest::limit(10, Side::Buy, 100, 3)
+            })
+            .unwrap();
+        assert!(trades.is_empty(), "{policy:?}");
+        assert_eq!(book.metrics().trades_generated, 0, "{policy:?}");
+        assert_eq!(book.last_trade_price(), None, "{policy:?}");
+        // Nothing crossed is left resting either.
+        assert!(!book.is_crossed(), "{policy:?}");
+    }
+}
+
+#[test]
+fn tape_accumulators_follow_fills_only() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 4).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 6).unwrap();
+    assert_eq!(book.last_trade_price(), None);
+    assert_eq!(book.last_trade_qty(), None);
+    assert_eq!(book.cumulative_volume(), Quantity(0));
+
+    book.place_limit_order(10, Side::Buy, 101, 7).unwrap();
+    assert_eq!(book.last_trade_price(), Some(Price(101)));
+    assert_eq!(book.last_trade_qty(), Some(Quantity(3)));
+    assert_eq!(book.cumulative_volume(), Quantity(7));
+
+    // Resting, amending and cancelling leave the tape alone.
+    book.place_limit_order(11, Side::Buy, 95, 5).unwrap();
+    book.modify_order(11, 96, 5).unwrap();
+    book.cancel_order(2).unwrap();
+    assert_eq!(book.last_trade_price(), Some(Price(101)));
+    assert_eq!(book.last_trade_qty(), Some(Quantity(3)));
+    assert_eq!(book.cumulative_volume(), Quantity(7));
+}
+
+/// Trade prices of a buy for 10 at 105 into asks of 5 at 100 and 102.
+fn sweep_prices(policy: Option<PricingPolicy>) -> Vec<Price> {
+    let mut book = OrderBook::new("TEST", 16);
+    if let Some(policy) = policy {
+        book.set_pricing_policy(policy);
+    }
+    book.place_limit_order(1, Side::Sell, 100, 5).unwrap();
+    book.place_limit_order(2, Side::Sell, 102, 5).unwrap();
+    let (_, trades) = book.place_limit_order(10, Side::Buy, 105, 10).unwrap();
+    assert_eq!(book.active_orders(), 0);
+    trades.iter().map(|t| t.price).collect()
+}
+
+#[test]
+fn maker_price_policy_is_the_default_behaviour() {
+    assert_eq!(sweep_prices(None), [Price(100), Price(102)]);
+    assert_eq!(
+        sweep_prices(Some(PricingPolicy::MakerPrice)),
+        sweep_prices(None)
+    );
+}
+
+#[test]
+fn midpoint_policy_splits_the_improvement() {
+    // 102.5 and 103.5: the odd half goes to the taker.
+    assert_eq!(
+        sweep_prices(Some(PricingPolicy::Midpoint)),
+        [Price(102), Price(103)]
+    );
+
+    let mut book = OrderBook::new("TEST", 16);
+    book.set_pricing_policy(PricingPolicy::Midpoint);
+    book.place_limit_order(1, Side::Buy, 100, 5).unwrap();
+    let (_, trades) = book.place_limit_order(10, Side::Sell, 95, 5).unwrap();
+    assert_eq!(trades[0].price, Price(98));
+    assert_eq!(book.last_trade_price(), Some(Price(98)));
+}
+
+#[test]
+fn midpoint_snaps_to_the_tick_toward_the_maker() {
+    let mid = |side, limit, maker, tick| {
+        PricingPolicy::Midpoint
+            .trade_price(side, limit, Price(maker), tick)
+            .0
+    };
+    assert_eq!(mid(Side::Buy, Some(Price(110)), 100, 4), 104);
+    assert_eq!(mid(Side::Sell, Some(Price(90)), 100, 4), 96);
+    // A market order has no limit to split.
+    assert_eq!(mid(Side::Buy, None, 100, 1), 100);
+}
+
+#[test]
+fn match_stream_dropped_after_two_of_three_fills() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let mut book = OrderBook::new("TEST", 16);
+    for (id, price) in [(1, 100), (2, 101), (3, 102)] {
+        book.place_limit_order(id, Side::Sell, price, 5).unwrap();
+    }
+    let observed = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&observed);
+    book.on_trade(move |_| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    let mut stream = book.match_stream(10, Side::Buy, 102, 15);
+    let first = stream.next().unwrap();
+    let second = stream.next().unwrap();
+    assert_eq!(stream.remaining(), Quantity(5));
+    drop(stream);
+
+    assert_eq!(
+        fills(&[first, second]),
+        [
+            (OrderId(1), Price(100), Quantity(5)),
+            (OrderId(2), Price(101), Quantity(5)),
+        ]
+    );
+    assert_eq!(observed.load(Ordering::Relaxed), 2);
+    // The third maker is untouched and the taker's remainder is gone.
+    assert_eq!(book.best_prices(), (None, Some(Price(102))));
+    assert_eq!(book.volume_at_price(Side::Sell, 102), Quantity(5));
+    assert_eq!(book.queue_position(10), None);
+
+    // The observer is back on the book after the drop.
+    book.place_limit_order(11, Side::Buy, 102, 1).unwrap();
+    assert_eq!(observed.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn iceberg_slices_coalesce_into_one_trade() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_iceberg(1, Side::Sell, 100, 5, 15).unwrap();
+
+    let (_, trades) = book.place_limit_order(10, Side::Buy, 100, 15).unwrap();
+    assert_eq!(trades.len(), 3);
+
+    let coalesced = coalesce_trades(&trades);
+    assert_eq!(fills(&coalesced), [(OrderId(1), Price(100), Quantity(15))]);
+    assert_eq!(coalesced[0].taker_id, OrderId(10));
+    assert_eq!(coalesced[0].seq, trades[2].seq);
+    assert_eq!(coalesced[0].maker_leaves_qty, Quantity(0));
+
+    // Merged quantities saturate instead of overflowing.
+    let mut huge = trades.clone();
+    huge[0].quantity = Quantity(u64::MAX);
+    assert_eq!(coalesce_trades(&huge)[0].quantity, Quantity(u64::MAX));
+}
+
+#[test]
+fn deep_sweep_stops_at_the_fifth_level_past_max_slippage() {
+    let mut book = OrderBook::new("TEST", 16);
+    for (id, price) in (1..=5).zip(100..) {
+        book.place_limit_order(id, Side::Sell, price, 5).unwrap();
+    }
+    let guard = TakerConstraints {
+        max_slippage: Some(Price(3)),
+        ..TakerConstraints::default()
+    };
+
+    let (unfilled, trades) = book
+        .place_market_order_with(10, Side::Buy, 25, guard)
+        .unwrap();
+
+    // Levels 100..=103 are within 3 of the best ask; 104 is not.
+    assert_eq!(unfilled, Quantity(5));
+    assert_eq!(
+        trades.iter().map(|t| t.price.0).collect::<Vec<_>>(),
+        [100, 101, 102, 103]
+    );
+    assert_eq!(book.best_prices(), (None, Some(Price(104))));
+    assert_eq!(book.volume_at_price(Side::Sell, 104), Quantity(5));
+    assert_eq!(book.queue_position(10), None);
+}
+
+#[test]
+fn aggressor_of_a_buy_taker_trade() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 5).unwrap();
+    let (_, trades) = book.place_limit_order(2, Side::Buy, 100, 3).unwrap();
+
+    let trade = &trades[0];
+    assert_eq!(trade.maker_side, Side::Sell);
+    assert_eq!(trade.aggressor_side(), Side::Buy);
+    assert!(trade.is_buy_aggressor());
+    assert_eq!(trade.notional(), 300);
+}
+
+#[test]
+fn aggressor_of_a_sell_taker_trade() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 100, 5).unwrap();
+    let (_, trades) = book.place_limit_order(2, Side::Sell, 100, 3).unwrap();
+
+    let trade = &trades[0];
+    assert_eq!(trade.maker_side, Side::Buy);
+    assert_eq!(trade.aggressor_side(), Side::Sell);
+    assert!(!trade.is_buy_aggressor());
+    assert_eq!(trade.notional(), 300);
+}
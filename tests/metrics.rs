@@ -0,0 +1,46 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::metrics::Metrics;
+use zero_alloc_lob::engine::request::OrderRequest;
+use zero_alloc_lob::storage::layout::Side;
+
+#[test]
+fn counters_after_a_known_sequence() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 10).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 10).unwrap();
+    book.place_limit_order(3, Side::Buy, 98, 10).unwrap();
+    // Sweeps order 1 and half of order 2: two trades.
+    book.place_limit_order(4, Side::Buy, 101, 15).unwrap();
+    book.modify_order(3, 98, 6).unwrap();
+    book.cancel_order(2).unwrap();
+    // Rests in the slot order 1 left behind.
+    book.place_limit_order(5, Side::Sell, 105, 1).unwrap();
+    // Rejected: not counted.
+    book.place_limit_order(6, Side::Sell, 0, 1).unwrap_err();
+
+    assert_eq!(
+        *book.metrics(),
+        Metrics {
+            orders_placed: 5,
+            orders_canceled: 1,
+            orders_modified: 1,
+            trades_generated: 2,
+            free_list_reuses: 1,
+        }
+    );
+}
+
+#[test]
+fn real_time_placements_are_counted() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 10).unwrap();
+    let mut trades = Vec::with_capacity(4);
+    book.try_place(OrderRequest::limit(2, Side::Buy, 100, 4), &mut trades)
+        .unwrap();
+    book.try_place(OrderRequest::limit(3, Side::Buy, 99, 4), &mut trades)
+        .unwrap();
+
+    let metrics = book.metrics();
+    assert_eq!(metrics.orders_placed, 3);
+    assert_eq!(metrics.trades_generated, 1);
+}
@@ -0,0 +1,250 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::engine::rate_limit::RateLimit;
+use zero_alloc_lob::engine::request::{ModifyMissingPolicy, OrderRequest};
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, Side};
+
+fn snapshot(book: &OrderBook) -> Vec<u8> {
+    let mut buf = Vec::new();
+    book.serialize(&mut buf);
+    buf
+}
+
+/// A book whose arena is exactly full: a bid at 99 and asks at 101 and 102.
+fn full_book() -> OrderBook {
+    let mut book = OrderBook::new("TEST", 3);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 5).unwrap();
+    book.place_limit_order(3, Side::Sell, 102, 5).unwrap();
+    assert!(matches!(
+        book.place_limit_order(4, Side::Buy, 90, 1),
+        Err(OrderBookError::ArenaFull)
+    ));
+    book
+}
+
+#[test]
+fn crossing_modify_in_a_full_arena_rests_in_its_own_slot() {
+    let mut book = full_book();
+    let (resting, trades) = book.modify_order(1, 101, 10).unwrap();
+
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].maker_id, OrderId(2));
+    assert_eq!(trades[0].quantity, Quantity(5));
+    assert!(resting.is_some());
+    assert_eq!(book.best_bid_price(), Some(Price(101)));
+    assert_eq!(book.volume_at_price(Side::Buy, 101), Quantity(5));
+    #[cfg(feature = "std")]
+    assert_eq!(book.validate(), Ok(()));
+}
+
+#[test]
+fn rejected_crossing_modify_in_a_full_arena_restores_the_order() {
+    let mut book = full_book();
+    // No tokens at all: the re-placement is refused after the pull.
+    book.set_rate_limit(Some(RateLimit {
+        burst: 0,
+        refill_interval: 1,
+    }));
+    let before = snapshot(&book);
+
+    assert!(matches!(
+        book.modify_order(1, 101, 10),
+        Err(OrderBookError::RateLimited)
+    ));
+    assert_eq!(snapshot(&book), before);
+    assert_eq!(book.active_orders(), 3);
+    #[cfg(feature = "std")]
+    assert_eq!(book.validate(), Ok(()));
+}
+
+/// Order 1 placed for 10 at 101, then filled for 4: 6 still rest.
+fn partially_filled() -> OrderBook {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 101, 10).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 10).unwrap();
+    book.place_limit_order(3, Side::Buy, 101, 4).unwrap();
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(16));
+    book
+}
+
+#[test]
+fn modify_of_partially_filled_order_cannot_restore_its_original_size() {
+    let mut book = partially_filled();
+    let before = snapshot(&book);
+
+    // Re-sending the original 10 would add 4 of fresh exposure.
+    assert!(matches!(
+        book.modify_order(1, 101, 10),
+        Err(OrderBookError::WouldIncreaseQty(OrderId(1)))
+    ));
+    assert!(matches!(
+        book.modify_order(1, 105, 7),
+        Err(OrderBookError::WouldIncreaseQty(OrderId(1)))
+    ));
+    assert_eq!(snapshot(&book), before);
+
+    // The resting 6 or less is fine, and a reduction keeps priority.
+    book.modify_order(1, 101, 5).unwrap();
+    assert_eq!(book.queue_position(1), Some(0));
+    book.modify_order(1, 102, 5).unwrap();
+    assert_eq!(book.volume_at_price(Side::Sell, 102), Quantity(5));
+}
+
+#[test]
+fn modify_increase_of_partially_filled_order_when_allowed() {
+    let mut book = partially_filled();
+    book.set_allow_modify_increase(true);
+
+    book.modify_order(1, 101, 10).unwrap();
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(20));
+    // An increase loses time priority.
+    assert_eq!(book.queue_position(1), Some(1));
+}
+
+#[test]
+fn reduce_only_rejects_an_increase_even_when_increases_are_allowed() {
+    let mut book = partially_filled();
+    book.set_allow_modify_increase(true);
+    let before = snapshot(&book);
+
+    assert!(matches!(
+        book.modify_reduce_only(1, 7),
+        Err(OrderBookError::WouldIncreaseQty(OrderId(1)))
+    ));
+    assert_eq!(snapshot(&book), before);
+
+    // A reduction keeps the head of the queue.
+    assert!(book.modify_reduce_only(1, 2).unwrap().is_some());
+    assert_eq!(book.queue_position(1), Some(0));
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(12));
+}
+
+#[test]
+fn reduce_only_to_zero_cancels() {
+    let mut book = partially_filled();
+    assert!(book.modify_reduce_only(1, 0).unwrap().is_none());
+    assert_eq!(book.queue_position(1), None);
+    assert_eq!(book.queue_position(2), Some(0));
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(10));
+    assert_eq!(book.metrics().orders_canceled, 1);
+}
+
+#[test]
+fn modify_of_a_missing_id_follows_the_policy() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 101, 5).unwrap();
+    let upsert = OrderRequest::limit(2, Side::Buy, 99, 3);
+
+    // `Reject` is the default.
+    assert!(matches!(
+        book.modify(upsert),
+        Err(OrderBookError::OrderNotFound(OrderId(2)))
+    ));
+    assert_eq!(book.active_orders(), 1);
+
+    book.set_modify_missing_policy(ModifyMissingPolicy::PlaceNew);
+    let (resting, trades) = book.modify(upsert).unwrap();
+    assert!(resting.is_some() && trades.is_empty());
+    assert_eq!(book.volume_at_price(Side::Buy, 99), Quantity(3));
+
+    // Now that it rests, the same request is an ordinary modify.
+    book.modify(OrderRequest::limit(2, Side::Buy, 98, 3))
+        .unwrap();
+    assert_eq!(book.best_bid_price(), Some(Price(98)));
+    assert_eq!(book.active_orders(), 2);
+}
+
+#[test]
+fn no_op_modify_keeps_priority_and_touches_nothing() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 101, 5).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 5).unwrap();
+    book.place_limit_order(3, Side::Sell, 102, 5).unwrap();
+    book.cancel_order(3).unwrap();
+    let before = snapshot(&book);
+    let (version, free_slots, metrics) = (book.version(), book.free_slots(), *book.metrics());
+
+    let (resting, trades) = book.modify_order(1, 101, 5).unwrap();
+    assert!(resting.is_some() && trades.is_empty());
+
+    assert_eq!(snapshot(&book), before);
+    assert_eq!(book.version(), version);
+    assert_eq!(book.free_slots(), free_slots);
+    assert_eq!(*book.metrics(), metrics);
+    assert_eq!(book.queue_position(1), Some(0));
+    #[cfg(feature = "std")]
+    assert_eq!(book.validate(), Ok(()));
+    // The level's tail is still order 2, so a newcomer queues behind it.
+    book.place_limit_order(4, Side::Sell, 101, 5).unwrap();
+    assert_eq!(book.queue_position(4), Some(2));
+}
+
+#[test]
+fn priority_guarded_modify_allows_a_decrease_and_rejects_a_move() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 10).unwrap();
+
+    book.modify_order_ex(1, 99, 6, true).unwrap();
+    assert_eq!(book.queue_position(1), Some(0));
+    assert_eq!(book.volume_at_price(Side::Buy, 99), Quantity(16));
+
+    let before = snapshot(&book);
+    assert!(matches!(
+        book.modify_order_ex(1, 98, 6, true),
+        Err(OrderBookError::WouldLosePriority(OrderId(1)))
+    ));
+    assert_eq!(snapshot(&book), before);
+    assert_eq!(book.queue_position(1), Some(0));
+}
+
+/// Three bids of 10 at 99, queued 1, 2, 3.
+fn three_at_99() -> OrderBook {
+    let mut book = OrderBook::new("TEST", 16);
+    for id in 1..=3 {
+        book.place_limit_order(id, Side::Buy, 99, 10).unwrap();
+    }
+    book
+}
+
+#[test]
+fn keep_priority_modify_only_ever_reduces_in_place() {
+    let mut book = three_at_99();
+    book.set_allow_modify_increase(true);
+
+    assert!(book.modify_order_keep_priority(1, 4).unwrap().is_some());
+    assert_eq!(book.queue_position(1), Some(0));
+    assert_eq!(book.volume_at_price(Side::Buy, 99), Quantity(24));
+
+    // Even with increases allowed, this method refuses to re-queue.
+    let before = snapshot(&book);
+    assert!(matches!(
+        book.modify_order_keep_priority(1, 5),
+        Err(OrderBookError::WouldLosePriority(OrderId(1)))
+    ));
+    assert_eq!(snapshot(&book), before);
+
+    assert!(book.modify_order_keep_priority(1, 0).unwrap().is_none());
+    assert_eq!(book.queue_position(2), Some(0));
+}
+
+#[test]
+fn same_price_increase_requeues_unless_priority_is_kept() {
+    let mut book = three_at_99();
+    book.set_allow_modify_increase(true);
+    book.modify_order(1, 99, 15).unwrap();
+    assert_eq!(book.queue_position(1), Some(2));
+
+    let mut book = three_at_99();
+    book.set_allow_modify_increase(true);
+    book.set_keep_priority_on_increase(true);
+    book.modify_order(1, 99, 15).unwrap();
+    assert_eq!(book.queue_position(1), Some(0));
+    assert_eq!(book.volume_at_price(Side::Buy, 99), Quantity(35));
+
+    // A price change re-queues regardless.
+    book.modify_order(2, 98, 10).unwrap();
+    book.modify_order(2, 99, 10).unwrap();
+    assert_eq!(book.queue_position(2), Some(2));
+}
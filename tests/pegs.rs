@@ -0,0 +1,111 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::storage::layout::{OrderId, Price, Side};
+
+fn price_of(book: &OrderBook, side: Side, id: u64) -> Option<Price> {
+    book.iter_side(side)
+        .find(|order| order.id == OrderId(id))
+        .map(|order| order.price)
+}
+
+#[test]
+fn peg_follows_a_better_opposite_best() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 110, 5).unwrap();
+    book.place_pegged(2, Side::Buy, -3, 4).unwrap();
+    assert_eq!(price_of(&book, Side::Buy, 2), Some(Price(107)));
+
+    // A better ask arrives: the peg should move down with it.
+    book.place_limit_order(3, Side::Sell, 108, 5).unwrap();
+    assert_eq!(book.reprice_pegs(), 1);
+    assert_eq!(price_of(&book, Side::Buy, 2), Some(Price(105)));
+
+    // Nothing moved since: repricing is a no-op.
+    assert_eq!(book.reprice_pegs(), 0);
+    #[cfg(feature = "std")]
+    assert_eq!(book.validate(), Ok(()));
+}
+
+#[test]
+fn peg_is_clamped_short_of_the_opposite_best() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Buy, 100, 5).unwrap();
+    book.place_pegged(2, Side::Sell, -5, 4).unwrap();
+    assert_eq!(price_of(&book, Side::Sell, 2), Some(Price(101)));
+    assert_eq!(book.best_bid_price(), Some(Price(100)));
+}
+
+#[test]
+fn peg_without_a_reference_is_rejected() {
+    let mut book = OrderBook::new("TEST", 16);
+    assert!(matches!(
+        book.place_pegged(1, Side::Buy, -1, 4),
+        Err(OrderBookError::InvalidPrice)
+    ));
+    assert_eq!(book.active_orders(), 0);
+}
+
+#[test]
+fn only_pegs_whose_reference_moved_are_repriced() {
+    let mut book = OrderBook::new("TEST", 64);
+    book.place_limit_order(1, Side::Buy, 100, 5).unwrap();
+    book.place_limit_order(2, Side::Sell, 110, 5).unwrap();
+    // Deep enough that no peg becomes the top of its own side.
+    for i in 0..5 {
+        book.place_pegged(10 + i, Side::Buy, -20, 1).unwrap();
+        book.place_pegged(20 + i, Side::Sell, 20, 1).unwrap();
+    }
+    assert_eq!(book.reprice_pegs(), 0);
+
+    // Only the ask moved: only the buy pegs follow.
+    book.cancel_order(2).unwrap();
+    book.place_limit_order(3, Side::Sell, 112, 5).unwrap();
+    assert_eq!(book.reprice_pegs(), 5);
+    assert_eq!(price_of(&book, Side::Buy, 10), Some(Price(92)));
+    assert_eq!(price_of(&book, Side::Sell, 20), Some(Price(120)));
+
+    // Only the bid moved: only the sell pegs follow.
+    book.place_limit_order(4, Side::Buy, 101, 5).unwrap();
+    assert_eq!(book.reprice_pegs(), 5);
+    assert_eq!(price_of(&book, Side::Sell, 24), Some(Price(121)));
+    assert_eq!(price_of(&book, Side::Buy, 14), Some(Price(92)));
+    #[cfg(feature = "std")]
+    assert_eq!(book.validate(), Ok(()));
+}
+
+#[test]
+fn peg_placed_against_another_reference_is_caught_up() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 110, 5).unwrap();
+    book.place_pegged(10, Side::Buy, -2, 1).unwrap();
+    assert_eq!(book.reprice_pegs(), 0);
+
+    // A better ask arrives and a second peg is priced against it before the
+    // first one is repriced.
+    book.place_limit_order(2, Side::Sell, 109, 5).unwrap();
+    book.place_pegged(11, Side::Buy, -4, 1).unwrap();
+    assert_eq!(price_of(&book, Side::Buy, 11), Some(Price(105)));
+    // The better ask goes away again: back to the group's old reference,
+    // yet peg 11 was priced against 109 and must still move.
+    book.cancel_order(2).unwrap();
+    assert_eq!(book.reprice_pegs(), 1);
+    assert_eq!(price_of(&book, Side::Buy, 10), Some(Price(108)));
+    assert_eq!(price_of(&book, Side::Buy, 11), Some(Price(106)));
+}
+
+#[test]
+fn cancelled_and_filled_pegs_are_dropped() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 110, 5).unwrap();
+    book.place_pegged(10, Side::Buy, -2, 1).unwrap();
+    book.place_pegged(11, Side::Buy, -3, 1).unwrap();
+    book.cancel_order(10).unwrap();
+    // Fills peg 11.
+    book.place_limit_order(2, Side::Sell, 107, 1).unwrap();
+    // Id 10 is reused by a plain order, which must not be moved.
+    book.place_limit_order(10, Side::Buy, 50, 1).unwrap();
+
+    book.place_limit_order(3, Side::Sell, 109, 5).unwrap();
+    assert_eq!(book.reprice_pegs(), 0);
+    assert_eq!(price_of(&book, Side::Buy, 10), Some(Price(50)));
+}
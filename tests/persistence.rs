@@ -0,0 +1,92 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::matcher::Trade;
+use zero_alloc_lob::engine::request::Command;
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, Side};
+
+fn snapshot(book: &OrderBook) -> Vec<u8> {
+    let mut buf = Vec::new();
+    book.serialize(&mut buf);
+    buf
+}
+
+fn place(id: u64, side: Side, price: u64, qty: u64) -> Command {
+    Command::Place {
+        id: OrderId(id),
+        side,
+        price: Price(price),
+        qty: Quantity(qty),
+    }
+}
+
+fn tape(trades: &[Trade]) -> Vec<(OrderId, OrderId, Price, Quantity, u64)> {
+    trades
+        .iter()
+        .map(|t| (t.maker_id, t.taker_id, t.price, t.quantity, t.seq))
+        .collect()
+}
+
+#[test]
+fn round_trip_keeps_depth_and_priority() {
+    let mut book = OrderBook::new("TEST", 32);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 4).unwrap();
+    book.place_limit_order(3, Side::Buy, 97, 6).unwrap();
+    book.place_limit_order(4, Side::Sell, 101, 8).unwrap();
+    book.place_limit_order(5, Side::Sell, 103, 2).unwrap();
+    book.place_limit_order(6, Side::Sell, 101, 3).unwrap();
+    // Partial fill and a cancel, so the book is not just what was placed.
+    book.place_limit_order(7, Side::Sell, 99, 3).unwrap();
+    book.cancel_order(5).unwrap();
+
+    let restored = OrderBook::deserialize("TEST", 32, &snapshot(&book)).unwrap();
+
+    assert_eq!(restored.depth_snapshot(10), book.depth_snapshot(10));
+    assert_eq!(restored.best_bid_price(), book.best_bid_price());
+    assert_eq!(restored.best_ask_price(), book.best_ask_price());
+    assert_eq!(restored.queue_position(2), book.queue_position(2));
+    assert_eq!(restored.next_seq(), book.next_seq());
+    assert_eq!(snapshot(&restored), snapshot(&book));
+    #[cfg(feature = "std")]
+    assert_eq!(restored.validate(), Ok(()));
+}
+
+#[test]
+fn replaying_the_same_commands_is_deterministic() {
+    let cmds = [
+        place(1, Side::Sell, 101, 10),
+        place(2, Side::Sell, 101, 5),
+        place(3, Side::Sell, 102, 7),
+        place(4, Side::Buy, 99, 8),
+        place(5, Side::Buy, 101, 12),
+        Command::Modify {
+            id: OrderId(3),
+            price: Price(100),
+            qty: Quantity(6),
+        },
+        Command::Cancel { id: OrderId(4) },
+        place(6, Side::Buy, 100, 9),
+        // Fails (unknown id) the same way on every replay.
+        Command::Cancel { id: OrderId(42) },
+        place(7, Side::Sell, 98, 4),
+    ];
+
+    let mut first = OrderBook::new("TEST", 32);
+    let mut second = OrderBook::new("TEST", 32);
+    let mut traded = 0;
+    for cmd in cmds {
+        let a = first.apply(cmd);
+        let b = second.apply(cmd);
+        assert_eq!(a.as_ref().err(), b.as_ref().err());
+        let (a, b) = (a.unwrap_or_default(), b.unwrap_or_default());
+        assert_eq!(tape(&a), tape(&b));
+        traded += a.len();
+    }
+    assert!(traded > 0);
+
+    assert_eq!(first.depth_snapshot(10), second.depth_snapshot(10));
+    assert_eq!(snapshot(&first), snapshot(&second));
+    assert_eq!(
+        snapshot(&OrderBook::replay("TEST", 32, &cmds)),
+        snapshot(&first)
+    );
+}
@@ -0,0 +1,77 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::engine::rate_limit::{RateLimit, RateLimiter};
+use zero_alloc_lob::engine::request::OrderRequest;
+use zero_alloc_lob::storage::layout::Side;
+
+fn order_at(id: u64, timestamp: u64) -> OrderRequest {
+    OrderRequest {
+        account: 1,
+        timestamp,
+        ..OrderRequest::limit(id, Side::Buy, 100 - id, 1)
+    }
+}
+
+#[test]
+fn account_over_its_burst_waits_for_a_refill() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.set_rate_limit(Some(RateLimit {
+        burst: 3,
+        refill_interval: 10,
+    }));
+
+    for id in 1..=3 {
+        book.place(order_at(id, 0)).unwrap();
+    }
+    assert!(matches!(
+        book.place(order_at(4, 9)),
+        Err(OrderBookError::RateLimited)
+    ));
+    assert_eq!(book.active_orders(), 3);
+
+    // One interval later there is exactly one token again.
+    book.place(order_at(4, 10)).unwrap();
+    assert!(matches!(
+        book.place(order_at(5, 10)),
+        Err(OrderBookError::RateLimited)
+    ));
+    assert_eq!(book.active_orders(), 4);
+}
+
+#[test]
+fn accounts_have_separate_buckets() {
+    let mut limiter = RateLimiter::new(RateLimit {
+        burst: 1,
+        refill_interval: 10,
+    });
+    assert!(limiter.try_acquire(1, 0));
+    assert!(!limiter.try_acquire(1, 0));
+    assert!(limiter.try_acquire(2, 0));
+}
+
+#[test]
+fn partial_intervals_carry_forward() {
+    let mut limiter = RateLimiter::new(RateLimit {
+        burst: 2,
+        refill_interval: 10,
+    });
+    assert!(limiter.try_acquire(1, 0));
+    assert!(limiter.try_acquire(1, 0));
+    // 15 units earn one token; the spare 5 count towards the next.
+    assert!(limiter.try_acquire(1, 15));
+    assert!(!limiter.try_acquire(1, 15));
+    assert!(limiter.try_acquire(1, 20));
+}
+
+#[test]
+fn huge_elapsed_time_refills_to_the_burst() {
+    let mut limiter = RateLimiter::new(RateLimit {
+        burst: 2,
+        refill_interval: 1,
+    });
+    // One token left, so the refill adds `u64::MAX` earned tokens to it.
+    assert!(limiter.try_acquire(1, 0));
+    assert!(limiter.try_acquire(1, u64::MAX));
+    assert!(limiter.try_acquire(1, u64::MAX));
+    assert!(!limiter.try_acquire(1, u64::MAX));
+}
@@ -0,0 +1,110 @@
+//! Allocation guarantees of the hard real-time path, checked with a counting
+//! global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::matcher::{SelfTradePrevention, Trade};
+use zero_alloc_lob::engine::request::{OrderRequest, PlacementOutcome};
+use zero_alloc_lob::storage::layout::{OrderId, RestMode, Side};
+
+struct CountingAlloc;
+
+thread_local! {
+    // Per thread, so tests running in parallel do not see each other.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Allocations made by `f` on this thread.
+fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn try_place_pulling_makers_does_not_allocate() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.set_self_trade_prevention(Some(SelfTradePrevention::CancelResting));
+    for id in 1..=4 {
+        book.place(OrderRequest {
+            rest_mode: RestMode::CancelIfCrossed,
+            ..OrderRequest::limit(id, Side::Sell, 100, 5)
+        })
+        .unwrap();
+    }
+    book.place(OrderRequest {
+        account: 7,
+        ..OrderRequest::limit(5, Side::Sell, 101, 5)
+    })
+    .unwrap();
+    book.place_limit_order(6, Side::Sell, 102, 5).unwrap();
+
+    let mut trades: Vec<Trade> = Vec::with_capacity(4);
+    let taker = OrderRequest {
+        account: 7,
+        ..OrderRequest::limit(99, Side::Buy, 102, 5)
+    };
+    let (outcome, count) = allocations(|| book.try_place(taker, &mut trades));
+
+    assert_eq!(outcome, Ok(PlacementOutcome::Filled));
+    assert_eq!(count, 0);
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].maker_id, OrderId(6));
+    let pulled: Vec<OrderId> = book.drain_auto_cancelled().collect();
+    assert_eq!(pulled, [1, 2, 3, 4, 5].map(OrderId));
+}
+
+#[test]
+fn try_place_leaves_stops_for_the_next_place() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 100, 5).unwrap();
+    book.place_limit_order(2, Side::Sell, 105, 5).unwrap();
+    book.place_stop_limit(3, Side::Buy, 100, 105, 5).unwrap();
+
+    let mut trades = Vec::with_capacity(4);
+    book.try_place(OrderRequest::limit(10, Side::Buy, 100, 5), &mut trades)
+        .unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(book.best_ask_price().map(|p| p.0), Some(105));
+
+    // The next allocating placement fires the stop the first trade reached.
+    let (_, stop_trades) = book.place_limit_order(11, Side::Buy, 90, 1).unwrap();
+    assert_eq!(stop_trades.len(), 1);
+    assert_eq!(stop_trades[0].taker_id, OrderId(3));
+}
+
+#[test]
+fn resting_without_a_match_does_not_allocate_trades() {
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_limit_order(1, Side::Sell, 101, 5).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 5).unwrap();
+
+    // Joins an existing level, so the level index has nothing to grow either.
+    let (result, count) = allocations(|| book.place_limit_order(3, Side::Buy, 99, 5));
+    let (resting, trades) = result.unwrap();
+
+    assert!(resting.is_some());
+    assert_eq!(trades.capacity(), 0);
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,165 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::matcher::{
+    MatchMode, SelfTradePrevention, TakerConstraints, Trade, match_footprint,
+};
+use zero_alloc_lob::engine::request::{OrderRequest, TimeInForce};
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, RestMode, Side};
+
+/// Everything a dry run could conceivably disturb.
+fn fingerprint(book: &OrderBook) -> (Vec<u8>, u64, u64, usize, usize) {
+    let mut buf = Vec::new();
+    book.serialize(&mut buf);
+    (
+        buf,
+        book.version(),
+        book.next_seq(),
+        book.used_bytes(),
+        book.free_slots(),
+    )
+}
+
+fn fills(trades: &[Trade]) -> Vec<(OrderId, Price, Quantity)> {
+    trades
+        .iter()
+        .map(|t| (t.maker_id, t.price, t.quantity))
+        .collect()
+}
+
+#[test]
+fn dry_run_leaves_the_book_byte_identical() {
+    let mut book = OrderBook::new("TEST", 64);
+    for i in 0..5 {
+        book.place_limit_order(i, Side::Sell, 100 + i, 10).unwrap();
+        book.place_limit_order(10 + i, Side::Buy, 99 - i, 10)
+            .unwrap();
+    }
+    book.place_iceberg(20, Side::Sell, 100, 4, 12).unwrap();
+    // Leave a hole on the free list too.
+    book.cancel_order(14).unwrap();
+    let before = fingerprint(&book);
+    let metrics = *book.metrics();
+
+    let trades = book.simulate_order(Side::Buy, 103, 45);
+    let (unfilled, _) = book.preview_match(Side::Sell, 1, 1_000);
+    let vwap = book.vwap(Side::Buy, 25);
+
+    assert!(!trades.is_empty());
+    assert_eq!(unfilled, Quantity(1_000 - 40));
+    assert!(vwap.is_some());
+    assert_eq!(fingerprint(&book), before);
+    assert_eq!(*book.metrics(), metrics);
+
+    // And the dry run predicted what really happens.
+    let (_, real) = book.place_limit_order(99, Side::Buy, 103, 45).unwrap();
+    assert_eq!(fills(&trades), fills(&real));
+}
+
+#[test]
+fn dry_run_requeues_iceberg_refills_like_the_matcher() {
+    // Iceberg 1 (display 10, total 20) queues ahead of order 2 (3 lots).
+    let mut book = OrderBook::new("TEST", 16);
+    book.place_iceberg(1, Side::Sell, 100, 10, 20).unwrap();
+    book.place_limit_order(2, Side::Sell, 100, 3).unwrap();
+
+    // The refill goes behind order 2: A 10, B 3, A 2.
+    let predicted = book.simulate_order(Side::Buy, 100, 15);
+    let (remaining, trade_count) = match_footprint(
+        &book,
+        Side::Buy,
+        Price(100),
+        Quantity(15),
+        TakerConstraints::default(),
+    );
+    let (_, real) = book.place_limit_order(3, Side::Buy, 100, 15).unwrap();
+
+    let expected = [
+        (OrderId(1), Price(100), Quantity(10)),
+        (OrderId(2), Price(100), Quantity(3)),
+        (OrderId(1), Price(100), Quantity(2)),
+    ];
+    assert_eq!(fills(&real), expected);
+    assert_eq!(fills(&predicted), expected);
+    assert_eq!((remaining, trade_count), (Quantity(0), 3));
+    let leaves =
+        |trades: &[Trade]| -> Vec<_> { trades.iter().map(|t| t.maker_leaves_qty).collect() };
+    assert_eq!(leaves(&predicted), leaves(&real));
+}
+
+/// Small xorshift generator, so the randomized test needs no dependency and
+/// replays identically.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+#[test]
+fn dry_run_agrees_with_matching_on_random_iceberg_books() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for round in 0..2_000 {
+        let mut book = OrderBook::new("TEST", 64);
+        book.set_match_mode(match rng.below(2) {
+            0 => MatchMode::PriceTime,
+            _ => MatchMode::ProRata,
+        });
+        book.set_self_trade_prevention(match rng.below(4) {
+            0 => Some(SelfTradePrevention::CancelResting),
+            1 => Some(SelfTradePrevention::CancelIncoming),
+            2 => Some(SelfTradePrevention::Decrement),
+            _ => None,
+        });
+        for id in 1..=rng.below(12) + 1 {
+            let total = rng.below(20) + 1;
+            let display = match rng.below(2) {
+                0 => 0,
+                _ => rng.below(total) + 1,
+            };
+            book.place(OrderRequest {
+                account: rng.below(3) as u32,
+                display_qty: Quantity(display),
+                rest_mode: match rng.below(8) {
+                    0 => RestMode::CancelIfCrossed,
+                    _ => RestMode::Normal,
+                },
+                ..OrderRequest::limit(id, Side::Sell, 100 + rng.below(4), total)
+            })
+            .unwrap();
+        }
+
+        let mut taker = OrderRequest {
+            tif: TimeInForce::Ioc,
+            account: rng.below(2) as u32,
+            ..OrderRequest::limit(99, Side::Buy, 100 + rng.below(5), rng.below(90) + 1)
+        };
+        if book.match_mode() == MatchMode::PriceTime && rng.below(3) == 0 {
+            taker.constraints.min_fill_per_maker = Some(Quantity(rng.below(6) + 1));
+        }
+
+        let (remaining, trade_count) = match_footprint(
+            &book,
+            taker.side,
+            taker.price,
+            taker.qty,
+            taker.taker_constraints(),
+        );
+        // Without constraints or an account, the dry run's trades too.
+        let plain = book.simulate_order(taker.side, taker.price.0, taker.qty.0);
+
+        let mut real_book = book.clone();
+        let (_, real) = real_book.place(taker).unwrap();
+        let traded: u64 = real.iter().map(|t| t.quantity.0).sum();
+        assert_eq!(trade_count, real.len(), "round {round}");
+        assert!(remaining.0 <= taker.qty.0 - traded);
+
+        let mut plain_book = book.clone();
+        let (_, plain_real) = plain_book
+            .place_limit_order_tif(99, Side::Buy, taker.price.0, taker.qty.0, TimeInForce::Ioc)
+            .unwrap();
+        assert_eq!(fills(&plain), fills(&plain_real), "round {round}");
+    }
+}
@@ -0,0 +1,41 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::storage::layout::{OrderId, Price, Side};
+
+#[test]
+fn one_sweep_fires_two_stacked_stops_oldest_first() {
+    let mut book = OrderBook::new("TEST", 16);
+    for (id, price) in [(1, 100), (2, 101), (3, 103), (4, 104)] {
+        book.place_limit_order(id, Side::Sell, price, 5).unwrap();
+    }
+    assert!(
+        book.place_stop_limit(20, Side::Buy, 100, 103, 5)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        book.place_stop_limit(21, Side::Buy, 101, 104, 5)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Trades through 100 and 101: both triggers are reached at once.
+    let (_, trades) = book.place_limit_order(10, Side::Buy, 101, 10).unwrap();
+    let prints: Vec<(OrderId, OrderId, Price)> = trades
+        .iter()
+        .map(|t| (t.taker_id, t.maker_id, t.price))
+        .collect();
+    assert_eq!(
+        prints,
+        [
+            (OrderId(10), OrderId(1), Price(100)),
+            (OrderId(10), OrderId(2), Price(101)),
+            (OrderId(20), OrderId(3), Price(103)),
+            (OrderId(21), OrderId(4), Price(104)),
+        ]
+    );
+    assert_eq!(book.active_orders(), 0);
+    assert_eq!(book.last_trade_price(), Some(Price(104)));
+    // Both stops are spent: they cannot be cancelled any more.
+    assert!(book.cancel_order(20).is_err());
+    assert!(book.cancel_order(21).is_err());
+}
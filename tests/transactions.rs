@@ -0,0 +1,79 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::engine::request::Command;
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, Side};
+
+fn snapshot(book: &OrderBook) -> Vec<u8> {
+    let mut buf = Vec::new();
+    book.serialize(&mut buf);
+    buf
+}
+
+fn place(id: u64, side: Side, price: u64, qty: u64) -> Command {
+    Command::Place {
+        id: OrderId(id),
+        side,
+        price: Price(price),
+        qty: Quantity(qty),
+    }
+}
+
+#[test]
+fn duplicate_id_mid_transaction_rolls_everything_back() {
+    let mut book = OrderBook::new("TEST", 32);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(3, Side::Sell, 101, 10).unwrap();
+    let before = snapshot(&book);
+
+    let cmds = [
+        place(10, Side::Buy, 98, 5),
+        Command::Cancel { id: OrderId(1) },
+        Command::Modify {
+            id: OrderId(3),
+            price: Price(102),
+            qty: Quantity(4),
+        },
+        place(11, Side::Sell, 103, 5),
+        // Collides with an order placed earlier in this transaction.
+        place(10, Side::Sell, 104, 5),
+        place(12, Side::Sell, 105, 5),
+    ];
+    assert_eq!(
+        book.transaction(&cmds).unwrap_err(),
+        OrderBookError::DuplicateId(OrderId(10))
+    );
+
+    // Same orders, same priority (order 1 back ahead of order 2), same seq
+    // counter: the serialized book is byte-identical.
+    assert_eq!(snapshot(&book), before);
+    #[cfg(feature = "std")]
+    assert_eq!(book.validate(), Ok(()));
+    assert_eq!(book.queue_position(1), Some(0));
+}
+
+#[test]
+fn committed_transaction_returns_its_trades() {
+    let mut book = OrderBook::new("TEST", 32);
+    book.place_limit_order(1, Side::Sell, 101, 10).unwrap();
+
+    let trades = book
+        .transaction(&[place(2, Side::Buy, 100, 5), place(3, Side::Sell, 102, 5)])
+        .unwrap();
+    assert!(trades.is_empty());
+    assert_eq!(book.active_orders(), 3);
+}
+
+#[test]
+fn trading_command_is_refused() {
+    let mut book = OrderBook::new("TEST", 32);
+    book.place_limit_order(1, Side::Sell, 101, 10).unwrap();
+    let before = snapshot(&book);
+
+    assert_eq!(
+        book.transaction(&[place(2, Side::Buy, 100, 5), place(3, Side::Buy, 101, 5)])
+            .unwrap_err(),
+        OrderBookError::PostOnlyWouldCross(OrderId(3))
+    );
+    assert_eq!(snapshot(&book), before);
+}
@@ -0,0 +1,232 @@
+use zero_alloc_lob::engine::book::OrderBook;
+use zero_alloc_lob::engine::error::OrderBookError;
+use zero_alloc_lob::engine::request::{OrderRequest, QuantityLimits, TimeInForce};
+use zero_alloc_lob::storage::layout::{OrderId, Price, Quantity, Side};
+
+/// A book with one resting order on each side and one slot on the free list.
+fn churned_book() -> OrderBook {
+    let mut book = OrderBook::new("TEST", 8);
+    book.place_limit_order(1, Side::Buy, 99, 10).unwrap();
+    book.place_limit_order(2, Side::Sell, 101, 10).unwrap();
+    book.place_limit_order(3, Side::Buy, 98, 10).unwrap();
+    book.cancel_order(3).unwrap();
+    book
+}
+
+#[test]
+fn zero_price_and_quantity_are_rejected_before_touching_storage() {
+    let mut book = churned_book();
+    let stats = book.arena_stats();
+    let used = book.used_bytes();
+
+    let rejects = [
+        (
+            OrderRequest::limit(10, Side::Buy, 0, 5),
+            OrderBookError::InvalidPrice,
+        ),
+        (
+            OrderRequest::limit(11, Side::Sell, 0, 5),
+            OrderBookError::InvalidPrice,
+        ),
+        (
+            OrderRequest::limit(12, Side::Buy, 100, 0),
+            OrderBookError::InvalidQuantity,
+        ),
+        (
+            OrderRequest::limit(13, Side::Sell, 100, 0),
+            OrderBookError::InvalidQuantity,
+        ),
+    ];
+    for (req, expected) in rejects {
+        assert_eq!(book.place(req).unwrap_err(), expected);
+        let mut trades = Vec::new();
+        assert_eq!(book.try_place(req, &mut trades).unwrap_err(), expected);
+        assert!(trades.is_empty());
+    }
+
+    assert_eq!(book.arena_stats(), stats);
+    assert_eq!(book.used_bytes(), used);
+    assert_eq!(book.active_orders(), 2);
+    // The ids were never indexed, so they are still free to use.
+    book.place_limit_order(10, Side::Buy, 97, 1).unwrap();
+}
+
+#[test]
+fn kill_switch_blocks_places_and_modifies_but_not_cancels() {
+    let mut book = churned_book();
+    book.set_kill_switch(true);
+    assert!(book.kill_switch_active());
+
+    assert_eq!(
+        book.place_limit_order(10, Side::Buy, 97, 1).unwrap_err(),
+        OrderBookError::KillSwitchActive
+    );
+    let mut trades = Vec::new();
+    assert_eq!(
+        book.try_place(OrderRequest::limit(10, Side::Buy, 97, 1), &mut trades)
+            .unwrap_err(),
+        OrderBookError::KillSwitchActive
+    );
+    assert_eq!(
+        book.modify_order(1, 98, 5).unwrap_err(),
+        OrderBookError::KillSwitchActive
+    );
+    assert_eq!(book.active_orders(), 2);
+
+    // Traders can still flatten.
+    book.cancel_order(1).unwrap();
+    book.cancel_order(2).unwrap();
+    assert_eq!(book.active_orders(), 0);
+
+    book.set_kill_switch(false);
+    book.place_limit_order(10, Side::Buy, 97, 1).unwrap();
+}
+
+#[test]
+fn on_grid_price_is_accepted() {
+    let mut book = OrderBook::new("TEST", 8);
+    book.set_tick_size(5);
+    book.place_limit_order(1, Side::Buy, 95, 1).unwrap();
+    book.place_limit_order(2, Side::Sell, 105, 1).unwrap();
+    assert_eq!(book.active_orders(), 2);
+}
+
+#[test]
+fn off_grid_price_is_rejected_before_matching() {
+    let mut book = OrderBook::new("TEST", 8);
+    book.set_tick_size(5);
+    book.place_limit_order(1, Side::Sell, 100, 5).unwrap();
+
+    // Would cross the ask, but no trade may print at a bad price.
+    assert_eq!(
+        book.place_limit_order(2, Side::Buy, 102, 5).unwrap_err(),
+        OrderBookError::InvalidPrice
+    );
+    assert_eq!(book.metrics().trades_generated, 0);
+    assert_eq!(book.active_orders(), 1);
+}
+
+#[test]
+fn tick_size_zero_or_one_accepts_any_price() {
+    for tick in [0, 1] {
+        let mut book = OrderBook::new("TEST", 8);
+        book.set_tick_size(tick);
+        book.place_limit_order(1, Side::Buy, 97, 1).unwrap();
+        book.place_limit_order(2, Side::Sell, 103, 1).unwrap();
+        assert_eq!(book.active_orders(), 2, "tick {tick}");
+    }
+}
+
+/// Lots of 10, between 20 and 100.
+fn lot_book() -> OrderBook {
+    let mut book = OrderBook::new("TEST", 8);
+    book.set_quantity_limits(QuantityLimits {
+        lot_size: 10,
+        min_qty: 20,
+        max_qty: 100,
+    });
+    book
+}
+
+#[test]
+fn quantity_off_the_lot_grid_is_rejected() {
+    let mut book = lot_book();
+    assert_eq!(
+        book.place_limit_order(1, Side::Buy, 99, 25).unwrap_err(),
+        OrderBookError::InvalidQuantity
+    );
+    book.place_limit_order(1, Side::Buy, 99, 30).unwrap();
+}
+
+#[test]
+fn quantity_below_the_minimum_is_rejected() {
+    let mut book = lot_book();
+    assert_eq!(
+        book.place_limit_order(1, Side::Buy, 99, 10).unwrap_err(),
+        OrderBookError::InvalidQuantity
+    );
+    book.place_limit_order(1, Side::Buy, 99, 20).unwrap();
+}
+
+#[test]
+fn quantity_above_the_maximum_is_rejected() {
+    let mut book = lot_book();
+    assert_eq!(
+        book.place_limit_order(1, Side::Buy, 99, 110).unwrap_err(),
+        OrderBookError::InvalidQuantity
+    );
+    book.place_limit_order(1, Side::Buy, 99, 100).unwrap();
+}
+
+#[test]
+fn quantity_limits_apply_to_market_and_ioc_orders() {
+    let mut book = lot_book();
+    book.place_limit_order(1, Side::Sell, 101, 50).unwrap();
+    assert_eq!(
+        book.place_market_order(2, Side::Buy, 15).unwrap_err(),
+        OrderBookError::InvalidQuantity
+    );
+    assert_eq!(
+        book.place_limit_order_tif(3, Side::Buy, 101, 15, TimeInForce::Ioc)
+            .unwrap_err(),
+        OrderBookError::InvalidQuantity
+    );
+    assert_eq!(book.volume_at_price(Side::Sell, 101), Quantity(50));
+}
+
+#[test]
+fn iceberg_slice_larger_than_the_order_is_rejected() {
+    let mut book = OrderBook::new("TEST", 8);
+    let req = OrderRequest {
+        display_qty: Quantity(11),
+        ..OrderRequest::limit(1, Side::Buy, 99, 10)
+    };
+    assert_eq!(
+        book.place(req).unwrap_err(),
+        OrderBookError::InvalidQuantity
+    );
+}
+
+#[test]
+fn contradictory_flags_are_rejected() {
+    let mut book = OrderBook::new("TEST", 8);
+    let post_only_ioc = OrderRequest {
+        post_only: true,
+        tif: TimeInForce::Ioc,
+        ..OrderRequest::limit(1, Side::Buy, 99, 10)
+    };
+    let iceberg_fok = OrderRequest {
+        display_qty: Quantity(5),
+        tif: TimeInForce::Fok,
+        ..OrderRequest::limit(2, Side::Buy, 99, 10)
+    };
+    for req in [post_only_ioc, iceberg_fok] {
+        assert_eq!(
+            book.place(req).unwrap_err(),
+            OrderBookError::InvalidOrderType
+        );
+    }
+    assert_eq!(book.active_orders(), 0);
+}
+
+#[test]
+fn crossing_post_only_is_rejected() {
+    let mut book = OrderBook::new("TEST", 8);
+    book.place_limit_order(1, Side::Sell, 101, 10).unwrap();
+    let req = OrderRequest {
+        post_only: true,
+        ..OrderRequest::limit(2, Side::Buy, 101, 10)
+    };
+    assert_eq!(
+        book.place(req).unwrap_err(),
+        OrderBookError::PostOnlyWouldCross(OrderId(2))
+    );
+    assert_eq!(book.metrics().trades_generated, 0);
+
+    // Just short of the ask it rests.
+    book.place(OrderRequest {
+        price: Price(100),
+        ..req
+    })
+    .unwrap();
+}